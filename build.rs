@@ -1,5 +1,3 @@
-use vergen;
-
 fn create_build_info() {
     use std::env;
     use std::fs::File;
@@ -17,12 +15,14 @@ fn create_build_info() {
         .join(", ");
     let mut string = format!(
         "/// Features the library was built with
+#[must_use]
 pub fn features() -> &'static str {{\n\t\"{}\"\n}}\n\n",
         features
     );
     f.write_all(&string.into_bytes()).unwrap();
     string = format!(
-        "/// Build profile information\n
+        "/// Build profile information
+#[must_use]
 pub fn profile() -> &'static str {{\n\t\"{}\"\n}}\n",
         env::var("PROFILE").unwrap()
     );
@@ -0,0 +1,362 @@
+//! A command-line client of the random.org api.
+//!
+//! The api key is read from the file passed with `--key-file`, the `RANDOM_ORG_API_KEY`
+//! environment variable or the `randomorg/key` file of the user's configuration directory, in
+//! this order.
+//!
+//! ```text
+//! $ randomorg integers --min 1 --max 6 -n 3
+//! 4
+//! 1
+//! 6
+//! $ randomorg --output json uuids -n 1
+//! ["5a4f5b38-4dca-4b7c-8b3e-7d5a0b8e6a0e"]
+//! ```
+
+#![deny(clippy::all)]
+#![deny(clippy::pedantic)]
+#![deny(missing_docs)]
+#![deny(warnings)]
+
+use base64::Engine;
+use clap::{Parser, Subcommand, ValueEnum};
+use randomorg::{AllowedCharacters, ApiKey, Error, ExactDecimal, Random, Result};
+use std::fmt::{Display, Write};
+use std::path::PathBuf;
+
+/// The environment variable the api key is read from.
+const KEY_VARIABLE: &str = "RANDOM_ORG_API_KEY";
+/// The characters of the strings when no alphabet is given.
+const DEFAULT_ALPHABET: &str = "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "randomorg",
+    version,
+    about = "True random numbers from random.org"
+)]
+struct Cli {
+    /// A file containing the api key.
+    #[arg(long, global = true)]
+    key_file: Option<PathBuf>,
+    /// The format of the output.
+    #[arg(short, long, global = true, value_enum, default_value_t = Output::Plain)]
+    output: Output,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Output {
+    /// One value per line.
+    Plain,
+    /// A JSON array or object.
+    Json,
+    /// A CSV table with a header row.
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum BlobFormat {
+    /// Base64, as the service returns the blobs.
+    Base64,
+    /// Lowercase hexadecimal digits.
+    Hex,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Generates random integers.
+    Integers {
+        /// The lower boundary of the integers, within the [-1e9, 1e9] range.
+        #[arg(long, allow_negative_numbers = true)]
+        min: i32,
+        /// The upper boundary of the integers, within the [-1e9, 1e9] range.
+        #[arg(long, allow_negative_numbers = true)]
+        max: i32,
+        /// How many integers to generate.
+        #[arg(short, default_value_t = 1)]
+        n: u64,
+        /// Picks the integers without replacement, so that they are all different.
+        #[arg(long)]
+        unique: bool,
+    },
+    /// Generates random decimal fractions within the [0, 1] range.
+    Decimals {
+        /// How many fractions to generate.
+        #[arg(short, default_value_t = 1)]
+        n: u64,
+        /// The number of decimal places, within the [1, 20] range.
+        #[arg(long, default_value_t = 10)]
+        places: u8,
+    },
+    /// Generates random numbers from a gaussian distribution.
+    Gaussians {
+        /// How many numbers to generate.
+        #[arg(short, default_value_t = 1)]
+        n: u64,
+        /// The mean of the distribution.
+        #[arg(long, allow_negative_numbers = true, default_value_t = 0.0)]
+        mean: f64,
+        /// The standard deviation of the distribution.
+        #[arg(long, default_value_t = 1.0)]
+        sd: f64,
+        /// The number of significant digits, within the [2, 20] range.
+        #[arg(long, default_value_t = 10)]
+        digits: u8,
+    },
+    /// Generates random strings.
+    Strings {
+        /// How many strings to generate.
+        #[arg(short, default_value_t = 1)]
+        n: u64,
+        /// The length of every string, within the [1, 20] range.
+        #[arg(long, default_value_t = 10)]
+        length: u8,
+        /// The characters the strings are made of, 80 at most.
+        #[arg(long, default_value = DEFAULT_ALPHABET)]
+        alphabet: String,
+    },
+    /// Generates random version 4 UUIDs.
+    Uuids {
+        /// How many UUIDs to generate.
+        #[arg(short, default_value_t = 1)]
+        n: u64,
+    },
+    /// Generates random blobs.
+    Blobs {
+        /// How many blobs to generate.
+        #[arg(short, default_value_t = 1)]
+        n: u64,
+        /// The size of every blob in bits, divisible by 8.
+        #[arg(long, default_value_t = 128)]
+        size: u32,
+        /// The encoding of the blobs.
+        #[arg(long, value_enum, default_value_t = BlobFormat::Base64)]
+        format: BlobFormat,
+    },
+    /// Prints the usage of the api key.
+    Usage,
+}
+
+/// Finds the api key: in the `--key-file` file, the environment variable or the configuration
+/// file, in this order.
+fn api_key(key_file: Option<PathBuf>) -> Result<ApiKey> {
+    if let Some(path) = key_file {
+        return ApiKey::from_file(path);
+    }
+    if std::env::var_os(KEY_VARIABLE).is_some() {
+        return ApiKey::from_env(KEY_VARIABLE);
+    }
+    match config_key_file() {
+        Some(path) if path.is_file() => ApiKey::from_file(path),
+        _ => Err(Error::Rest(
+            "No api key found: pass --key-file, set RANDOM_ORG_API_KEY or write the key to \
+             ~/.config/randomorg/key.",
+        )),
+    }
+}
+
+/// Returns the path of the key file in the user's configuration directory.
+fn config_key_file() -> Option<PathBuf> {
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config.join("randomorg").join("key"))
+}
+
+/// Quotes a CSV field when it contains a separator, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Renders a list of values in the output format.
+fn render_values<T: Display + serde::Serialize>(values: &[T], output: Output) -> Result<String> {
+    if output == Output::Json {
+        return Ok(format!("{}\n", serde_json::to_string(values)?));
+    }
+    let header = if output == Output::Csv { "value\n" } else { "" };
+    Ok(values.iter().fold(header.to_owned(), |mut rendered, v| {
+        let v = v.to_string();
+        let v = if output == Output::Csv {
+            csv_field(&v)
+        } else {
+            v
+        };
+        let _ = writeln!(rendered, "{v}");
+        rendered
+    }))
+}
+
+/// Renders the fields of an object in the output format.
+fn render_object<T: serde::Serialize>(object: &T, output: Output) -> Result<String> {
+    let value = serde_json::to_value(object)?;
+    if output == Output::Json {
+        return Ok(format!("{value}\n"));
+    }
+    let fields = value
+        .as_object()
+        .ok_or(Error::Rest("Only objects may be rendered as fields."))?
+        .iter()
+        .map(|(name, value)| match value {
+            serde_json::Value::String(s) => (name.as_str(), s.clone()),
+            value => (name.as_str(), value.to_string()),
+        })
+        .collect::<Vec<_>>();
+    if output == Output::Plain {
+        return Ok(fields
+            .iter()
+            .fold(String::new(), |mut rendered, (name, value)| {
+                let _ = writeln!(rendered, "{name}: {value}");
+                rendered
+            }));
+    }
+    let row = |cells: Vec<String>| format!("{}\n", cells.join(","));
+    Ok(
+        row(fields.iter().map(|(name, _)| csv_field(name)).collect())
+            + &row(fields.iter().map(|(_, value)| csv_field(value)).collect()),
+    )
+}
+
+/// Re-encodes a base64 blob of the service as hexadecimal digits.
+fn hex_blob(blob: &str) -> Result<String> {
+    let bytes = base64::engine::general_purpose::STANDARD.decode(blob)?;
+    Ok(bytes.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    }))
+}
+
+fn run(cli: Cli) -> Result<String> {
+    let random = Random::new(api_key(cli.key_file)?);
+    let output = cli.output;
+    match cli.command {
+        Command::Integers {
+            min,
+            max,
+            n,
+            unique,
+        } => {
+            let data = random
+                .request_integers()
+                .min(min)
+                .max(max)
+                .count(n)
+                .replacement(!unique)
+                .collect::<Vec<i32>>()?;
+            render_values(&data, output)
+        }
+        Command::Decimals { n, places } => {
+            let data = random
+                .request_decimal_fractions()
+                .count(n)
+                .decimal_places(places)
+                .collect_exact::<Vec<ExactDecimal>>()?;
+            render_values(&data, output)
+        }
+        Command::Gaussians {
+            n,
+            mean,
+            sd,
+            digits,
+        } => {
+            let data = random
+                .request_gaussians()
+                .count(n)
+                .mean(mean)
+                .standard_deviation(sd)
+                .significant_digits(digits)
+                .collect_exact::<Vec<ExactDecimal>>()?;
+            render_values(&data, output)
+        }
+        Command::Strings {
+            n,
+            length,
+            alphabet,
+        } => {
+            let data = random
+                .request_strings()
+                .count(n)
+                .length(length)
+                .characters(AllowedCharacters(alphabet.chars().collect()))
+                .collect::<Vec<String>>()?;
+            render_values(&data, output)
+        }
+        Command::Uuids { n } => {
+            let data = random.request_uuids().count(n).collect::<Vec<String>>()?;
+            render_values(&data, output)
+        }
+        Command::Blobs { n, size, format } => {
+            let data = random
+                .request_blobs()
+                .count(n)
+                .size(size)
+                .collect::<Vec<String>>()?;
+            let data = match format {
+                BlobFormat::Base64 => data,
+                BlobFormat::Hex => data.iter().map(|b| hex_blob(b)).collect::<Result<_>>()?,
+            };
+            render_values(&data, output)
+        }
+        Command::Usage => render_object(&random.get_usage()?.result, output),
+    }
+}
+
+fn main() {
+    match run(Cli::parse()) {
+        Ok(rendered) => print!("{rendered}"),
+        Err(e) => {
+            let mut message = e.to_string();
+            let mut source = std::error::Error::source(&e);
+            while let Some(inner) = source {
+                let _ = write!(message, ": {inner}");
+                source = inner.source();
+            }
+            eprintln!("randomorg: {message}");
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hex_blob, render_object, render_values, Output};
+
+    #[test]
+    fn test_values_rendering() {
+        let values = vec!["a".to_owned(), "b,\"c\"".to_owned()];
+        assert_eq!(
+            render_values(&values, Output::Plain).unwrap(),
+            "a\nb,\"c\"\n"
+        );
+        assert_eq!(
+            render_values(&values, Output::Json).unwrap(),
+            "[\"a\",\"b,\\\"c\\\"\"]\n"
+        );
+        assert_eq!(
+            render_values(&values, Output::Csv).unwrap(),
+            "value\na\n\"b,\"\"c\"\"\"\n"
+        );
+        assert_eq!(render_values(&[-1, 2], Output::Json).unwrap(), "[-1,2]\n");
+    }
+
+    #[test]
+    fn test_object_rendering() {
+        let usage = serde_json::json!({ "bitsLeft": 250_000, "status": "running" });
+        assert_eq!(
+            render_object(&usage, Output::Plain).unwrap(),
+            "bitsLeft: 250000\nstatus: running\n"
+        );
+        assert_eq!(
+            render_object(&usage, Output::Csv).unwrap(),
+            "bitsLeft,status\n250000,running\n"
+        );
+        assert!(render_object(&[1], Output::Plain).is_err());
+        assert_eq!(hex_blob("AP8Q").unwrap(), "00ff10");
+    }
+}
@@ -1,35 +1,98 @@
-//! This module contains custom deserialization for non iso8601 dates given by random.org.
-//! If there is no incorrect date - it will use default deserialization of `chrono` crate.
+//! This module contains custom (de)serialization for non iso8601 dates given by random.org.
+//!
+//! The service writes its UTC dates as `2011-10-10 13:19:12Z`, with a space instead of the `T`
+//! of ISO 8601. Both forms are accepted, with or without fractional seconds, as well as RFC 3339
+//! dates with an explicit offset. The dates are serialized back the way the service writes them.
 
 use std::fmt;
 
-use chrono::NaiveDateTime;
-use serde::de;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{de, Serializer};
 
-struct NaiveDateTimeFromMyFormatVisitor;
+/// The format the service writes its dates in; the fractional seconds are only written when
+/// there are any.
+const SERVICE_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.fZ";
 
-pub fn deserialize<'de, D>(d: D) -> Result<NaiveDateTime, D::Error>
+/// The formats of the dates without an offset, which are in UTC.
+const UTC_FORMATS: [&str; 2] = ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"];
+
+struct DateTimeFromMyFormatVisitor;
+
+pub fn deserialize<'de, D>(d: D) -> Result<DateTime<Utc>, D::Error>
 where
     D: de::Deserializer<'de>,
 {
-    Ok(d.deserialize_str(NaiveDateTimeFromMyFormatVisitor)?)
+    d.deserialize_str(DateTimeFromMyFormatVisitor)
 }
 
-impl<'de> de::Visitor<'de> for NaiveDateTimeFromMyFormatVisitor {
-    type Value = NaiveDateTime;
+pub fn serialize<S: Serializer>(date: &DateTime<Utc>, s: S) -> Result<S::Ok, S::Error> {
+    s.collect_str(&date.format(SERVICE_FORMAT))
+}
+
+/// Parses a date in any of the accepted forms.
+fn parse(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Some(date.with_timezone(&Utc));
+    }
+    let value = value.strip_suffix('Z').unwrap_or(value);
+    UTC_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|date| date.and_utc())
+}
+
+impl de::Visitor<'_> for DateTimeFromMyFormatVisitor {
+    type Value = DateTime<Utc>;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         write!(formatter, "a datetime string")
     }
 
-    fn visit_str<E>(self, value: &str) -> Result<NaiveDateTime, E>
+    fn visit_str<E>(self, value: &str) -> Result<DateTime<Utc>, E>
     where
         E: de::Error,
     {
-        match NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%SZ") {
-            Ok(d) => Ok(d),
-            Err(_) => NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%SZ")
-                .map_err(|e| E::custom(format!("Parse error {} for {}", e, value))),
+        parse(value).ok_or_else(|| E::custom(format!("Invalid datetime {value}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, SERVICE_FORMAT};
+    use chrono::{TimeZone, Timelike, Utc};
+
+    #[test]
+    fn test_accepted_formats() {
+        let date = Utc.with_ymd_and_hms(2011, 10, 10, 13, 19, 12).unwrap();
+
+        assert_eq!(parse("2011-10-10 13:19:12Z"), Some(date));
+        assert_eq!(parse("2011-10-10 13:19:12"), Some(date));
+        assert_eq!(parse("2011-10-10T13:19:12Z"), Some(date));
+        assert_eq!(parse("2011-10-10T15:19:12+02:00"), Some(date));
+
+        let fractional = parse("2011-10-10 13:19:12.345Z").unwrap();
+        assert_eq!(fractional.with_nanosecond(0), Some(date));
+        assert_eq!(fractional.nanosecond(), 345_000_000);
+        let fractional = parse("2011-10-10T13:19:12.000123456Z").unwrap();
+        assert_eq!(fractional.with_nanosecond(0), Some(date));
+        assert_eq!(fractional.nanosecond(), 123_456);
+    }
+
+    #[test]
+    fn test_rejected_formats() {
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("2011-10-10"), None);
+        assert_eq!(parse("10/10/2011 13:19:12"), None);
+        assert_eq!(parse("2011-10-10 13:19:12ZZ"), None);
+    }
+
+    #[test]
+    fn test_service_format_round_trips() {
+        for date in ["2011-10-10 13:19:12Z", "2011-10-10 13:19:12.345Z"] {
+            assert_eq!(
+                parse(date).unwrap().format(SERVICE_FORMAT).to_string(),
+                date
+            );
         }
     }
 }
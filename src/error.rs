@@ -1,3 +1,4 @@
+use base64::DecodeError as Base64Error;
 use reqwest::Error as ReqwestError;
 use serde_json::Error as JsonError;
 use std::error::Error as StdError;
@@ -9,9 +10,33 @@ use std::num::ParseIntError;
 pub type Result<T> = ::std::result::Result<T, Error>;
 
 /// Random.org error code.
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, serde::Deserialize)]
 pub struct ErrorCode(pub u64);
 
+impl ErrorCode {
+    /// The api key does not exist.
+    pub const KEY_DOES_NOT_EXIST: ErrorCode = ErrorCode(400);
+    /// The api key is not running (it has been stopped or paused).
+    pub const KEY_NOT_RUNNING: ErrorCode = ErrorCode(401);
+    /// The request would exceed the daily request allowance of the api key.
+    pub const REQUEST_ALLOWANCE_EXCEEDED: ErrorCode = ErrorCode(402);
+    /// The request would exceed the daily bit allowance of the api key.
+    pub const BIT_ALLOWANCE_EXCEEDED: ErrorCode = ErrorCode(403);
+
+    /// Returns `true` if the service refused to serve the api key itself, so the request may
+    /// succeed with another key.
+    #[must_use]
+    pub fn is_key_unusable(self) -> bool {
+        [
+            ErrorCode::KEY_DOES_NOT_EXIST,
+            ErrorCode::KEY_NOT_RUNNING,
+            ErrorCode::REQUEST_ALLOWANCE_EXCEEDED,
+            ErrorCode::BIT_ALLOWANCE_EXCEEDED,
+        ]
+        .contains(&self)
+    }
+}
+
 /// Random.org response error definition.
 ///
 /// If an error occurred, this member contains a service-specific error object with details
@@ -41,12 +66,18 @@ pub enum Error {
     Io(IoError),
     /// A parse number error
     ParseIntError(ParseIntError),
-    /// A error common toornament service error
+    /// A blob decoding error
+    Base64(Base64Error),
+    /// An error returned by the random.org service, with the HTTP status of the response
     RandomOrg(::reqwest::StatusCode, ResponseError),
     /// A generic non-success response from the REST API
     Status(::reqwest::StatusCode, String),
     /// A rest-api error
     Rest(&'static str),
+    /// None of the api keys may be used to perform a request
+    NoUsableKey,
+    /// The request was not sent because it would break the client's budget
+    BudgetExceeded(&'static str),
 }
 
 impl From<ParseIntError> for Error {
@@ -76,6 +107,18 @@ impl From<::reqwest::blocking::Response> for Error {
     }
 }
 
+impl From<Base64Error> for Error {
+    fn from(e: Base64Error) -> Error {
+        Error::Base64(e)
+    }
+}
+
+impl From<std::convert::Infallible> for Error {
+    fn from(e: std::convert::Infallible) -> Error {
+        match e {}
+    }
+}
+
 impl From<IoError> for Error {
     fn from(err: IoError) -> Error {
         Error::Io(err)
@@ -97,10 +140,28 @@ impl From<JsonError> for Error {
 impl Display for Error {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         match *self {
-            Error::Reqwest(ref inner) => inner.fmt(f),
-            Error::Json(ref inner) => inner.fmt(f),
-            Error::Io(ref inner) => inner.fmt(f),
-            _ => f.write_str(&self.to_string()),
+            // The wrapped errors are returned by `source`, so they are not repeated here. An I/O
+            // error often carries nothing but its message, so it is shown here instead, and
+            // `source` skips to the error it wraps.
+            Error::Reqwest(_) => f.write_str("HTTP request failed"),
+            Error::Json(_) => f.write_str("Could not parse the JSON"),
+            Error::Io(ref e) => write!(f, "I/O error: {e}"),
+            Error::ParseIntError(_) => f.write_str("Could not parse an integer"),
+            Error::Base64(_) => f.write_str("Could not decode a base64 blob"),
+            Error::RandomOrg(status, ref e) => write!(
+                f,
+                "random.org error {} (HTTP {status}): {}",
+                e.code.0, e.message
+            ),
+            Error::Status(status, ref body) if body.is_empty() => {
+                write!(f, "Unexpected HTTP status {status}")
+            }
+            Error::Status(status, ref body) => write!(f, "Unexpected HTTP status {status}: {body}"),
+            Error::Rest(message) => f.write_str(message),
+            Error::NoUsableKey => {
+                f.write_str("None of the api keys may be used to perform a request")
+            }
+            Error::BudgetExceeded(message) => write!(f, "Budget exceeded: {message}"),
         }
     }
 }
@@ -110,9 +171,79 @@ impl StdError for Error {
         match *self {
             Error::Reqwest(ref inner) => Some(inner),
             Error::Json(ref inner) => Some(inner),
-            Error::Io(ref inner) => Some(inner),
+            Error::Io(ref inner) => inner.source(),
             Error::ParseIntError(ref inner) => Some(inner),
+            Error::Base64(ref inner) => Some(inner),
             _ => None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, ErrorCode, ResponseError};
+    use reqwest::StatusCode;
+    use std::error::Error as StdError;
+
+    #[test]
+    fn test_wrapped_errors_display_and_chain() {
+        let reqwest = reqwest::blocking::Client::new()
+            .get("not a url")
+            .send()
+            .unwrap_err();
+        let json = serde_json::from_str::<u8>("x").unwrap_err();
+        let parse = "x".parse::<u8>().unwrap_err();
+        let base64 = crate::reader::decode_blob("!").unwrap_err();
+
+        let cases = vec![
+            (Error::from(reqwest), "HTTP request failed"),
+            (Error::from(json), "Could not parse the JSON"),
+            (Error::from(parse), "Could not parse an integer"),
+            (base64, "Could not decode a base64 blob"),
+        ];
+        for (error, message) in cases {
+            let source = error.source().expect("A wrapped error must be the source.");
+            assert_eq!(error.to_string(), message);
+            assert!(!source.to_string().is_empty());
+        }
+
+        let io = Error::from(std::io::Error::other("disk is full"));
+        assert_eq!(io.to_string(), "I/O error: disk is full");
+        assert!(io.source().is_none());
+    }
+
+    #[test]
+    fn test_own_errors_display() {
+        let random_org = Error::RandomOrg(
+            StatusCode::OK,
+            ResponseError {
+                code: ErrorCode::KEY_NOT_RUNNING,
+                message: "Your API key is not running".to_owned(),
+            },
+        );
+        assert_eq!(
+            random_org.to_string(),
+            "random.org error 401 (HTTP 200 OK): Your API key is not running"
+        );
+        assert_eq!(
+            Error::Status(StatusCode::BAD_GATEWAY, "Try later".to_owned()).to_string(),
+            "Unexpected HTTP status 502 Bad Gateway: Try later"
+        );
+        assert_eq!(
+            Error::Status(StatusCode::BAD_GATEWAY, String::new()).to_string(),
+            "Unexpected HTTP status 502 Bad Gateway"
+        );
+        assert_eq!(Error::Rest("Failed.").to_string(), "Failed.");
+        assert_eq!(
+            Error::NoUsableKey.to_string(),
+            "None of the api keys may be used to perform a request"
+        );
+        assert_eq!(
+            Error::BudgetExceeded("Too many requests.").to_string(),
+            "Budget exceeded: Too many requests."
+        );
+        for error in [random_org, Error::Rest("Failed."), Error::NoUsableKey] {
+            assert!(error.source().is_none());
+        }
+    }
+}
@@ -0,0 +1,401 @@
+//! Dice, coins and cards for games, played with the values generated by random.org.
+//!
+//! Every helper returns the service's `RandomResult` the values come from alongside the
+//! outcome, so that the outcome may be checked later.
+//!
+//! # Usage
+//!
+//! ```rust,no_run
+//! use randomorg::games::{flip_coins, roll, Deck};
+//! use randomorg::Random;
+//!
+//! let random = Random::new("API KEY HERE");
+//! let roll = roll(&random, "3d6+2").unwrap();
+//! println!("Rolled {} ({:?})", roll.total, roll.dice);
+//! let flips = flip_coins(&random, 3).unwrap();
+//! println!("Flipped {:?}", flips.coins);
+//! let mut deck = Deck::standard();
+//! deck.shuffle(&random).unwrap();
+//! println!("Dealt {:?}", deck.deal(5));
+//! ```
+
+use crate::{Error, GenerateIntegersResult, Permutation, Random, Result};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+/// The most sides a die may have: the service generates integers up to 1e9.
+const MAX_SIDES: u32 = 1_000_000_000;
+/// The most dice a term may roll.
+const MAX_DICE: u32 = 10_000;
+
+/// A number of dice with the same number of sides, in a dice notation.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct DiceTerm {
+    /// The number of dice rolled.
+    pub count: u32,
+    /// The number of sides of every die, numbered from 1.
+    pub sides: u32,
+    /// Whether the dice are subtracted from the total instead of added to it.
+    pub negative: bool,
+}
+
+/// A dice notation, like `3d6+2`, `d20` or `2d8+1d6-1`. `d%` stands for `d100`.
+///
+/// A term rolls at most 10,000 dice of at most 1e9 sides.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Dice {
+    /// The dice terms, in the order of the notation.
+    pub terms: Vec<DiceTerm>,
+    /// The sum of the constant terms.
+    pub modifier: i64,
+}
+
+impl FromStr for Dice {
+    type Err = Error;
+
+    fn from_str(notation: &str) -> Result<Dice> {
+        const INVALID: Error = Error::Rest("Invalid dice notation.");
+
+        let notation = notation
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>();
+        let mut dice = Dice {
+            terms: Vec::new(),
+            modifier: 0,
+        };
+        let mut rest = notation.as_str();
+        while !rest.is_empty() {
+            let negative = rest.starts_with('-');
+            if negative || rest.starts_with('+') {
+                rest = &rest[1..];
+            }
+            let end = rest.find(['+', '-']).unwrap_or(rest.len());
+            let term = &rest[..end];
+            rest = &rest[end..];
+            if let Some((count, sides)) = term.split_once(['d', 'D']) {
+                let count = if count.is_empty() {
+                    1
+                } else {
+                    count.parse().map_err(|_| INVALID)?
+                };
+                let sides = if sides == "%" {
+                    100
+                } else {
+                    sides.parse().map_err(|_| INVALID)?
+                };
+                if count == 0 || count > MAX_DICE || sides == 0 || sides > MAX_SIDES {
+                    return Err(INVALID);
+                }
+                dice.terms.push(DiceTerm {
+                    count,
+                    sides,
+                    negative,
+                });
+            } else {
+                let constant = term.parse::<i64>().map_err(|_| INVALID)?;
+                let constant = if negative { -constant } else { constant };
+                dice.modifier = dice.modifier.checked_add(constant).ok_or(INVALID)?;
+            }
+        }
+        if dice.terms.is_empty() {
+            return Err(Error::Rest("The dice notation must roll at least one die."));
+        }
+        Ok(dice)
+    }
+}
+
+/// The outcome of a dice roll.
+#[derive(Debug, Clone)]
+pub struct Roll {
+    /// The rolled dice notation.
+    pub notation: Dice,
+    /// The values of the dice of every term of the notation.
+    pub dice: Vec<Vec<i32>>,
+    /// The sum of the dice and of the modifier.
+    pub total: i64,
+    /// The results of the service the dice of every term come from.
+    pub results: Vec<GenerateIntegersResult>,
+}
+
+/// Rolls the dice of a dice notation, like `3d6+2`, with a call per dice term.
+///
+/// # Errors
+///
+/// Returns `Error::Rest` if the notation is invalid or the total overflows an `i64`, and the
+/// error of the service if a call fails.
+pub fn roll(random: &Random, notation: &str) -> Result<Roll> {
+    let notation = notation.parse::<Dice>()?;
+    let mut dice = Vec::with_capacity(notation.terms.len());
+    let mut results = Vec::with_capacity(notation.terms.len());
+    let mut total = notation.modifier;
+    for term in &notation.terms {
+        let result = random
+            .request_integers()
+            .min(1)
+            .max(i32::try_from(term.sides).unwrap_or(i32::MAX))
+            .count(u64::from(term.count))
+            .replacement(true)
+            .collect::<crate::Response<GenerateIntegersResult>>()?
+            .result;
+        let sum = result
+            .random
+            .data
+            .iter()
+            .copied()
+            .map(i64::from)
+            .sum::<i64>();
+        total = if term.negative {
+            total.checked_sub(sum)
+        } else {
+            total.checked_add(sum)
+        }
+        .ok_or(Error::Rest("The total of the dice roll overflows."))?;
+        dice.push(result.random.data.clone());
+        results.push(result);
+    }
+    Ok(Roll {
+        notation,
+        dice,
+        total,
+        results,
+    })
+}
+
+/// A side of a coin.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Coin {
+    /// The heads side, drawn as 0.
+    Heads,
+    /// The tails side, drawn as 1.
+    Tails,
+}
+
+/// The outcome of coin flips.
+#[derive(Debug, Clone)]
+pub struct CoinFlips {
+    /// The sides the coins landed on.
+    pub coins: Vec<Coin>,
+    /// The result of the service the flips come from. It is `None` when no coin was flipped
+    /// and no call was made.
+    pub result: Option<GenerateIntegersResult>,
+}
+
+/// Flips `count` coins, drawing a bit for every one.
+///
+/// # Errors
+///
+/// Returns the error of the service if a call fails.
+pub fn flip_coins(random: &Random, count: u64) -> Result<CoinFlips> {
+    if count == 0 {
+        return Ok(CoinFlips {
+            coins: Vec::new(),
+            result: None,
+        });
+    }
+    let result = random
+        .request_integers()
+        .min(0)
+        .max(1)
+        .count(count)
+        .replacement(true)
+        .collect::<crate::Response<GenerateIntegersResult>>()?
+        .result;
+    let coins = result
+        .random
+        .data
+        .iter()
+        .map(|&side| if side == 0 { Coin::Heads } else { Coin::Tails })
+        .collect();
+    Ok(CoinFlips {
+        coins,
+        result: Some(result),
+    })
+}
+
+/// A suit of the standard deck.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Suit {
+    /// ♣
+    Clubs,
+    /// ♦
+    Diamonds,
+    /// ♥
+    Hearts,
+    /// ♠
+    Spades,
+}
+
+/// A card of the standard deck. The rank goes from 2 to 14, the jack being 11, the queen 12,
+/// the king 13 and the ace 14.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Card {
+    /// The rank of the card, within the [2; 14] range.
+    pub rank: u8,
+    /// The suit of the card.
+    pub suit: Suit,
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rank = match self.rank {
+            11 => "J".to_owned(),
+            12 => "Q".to_owned(),
+            13 => "K".to_owned(),
+            14 => "A".to_owned(),
+            rank => rank.to_string(),
+        };
+        let suit = match self.suit {
+            Suit::Clubs => '♣',
+            Suit::Diamonds => '♦',
+            Suit::Hearts => '♥',
+            Suit::Spades => '♠',
+        };
+        write!(f, "{rank}{suit}")
+    }
+}
+
+/// A deck of cards, shuffled with permutations drawn from the service. The top of the deck
+/// is its first card.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Deck<C = Card> {
+    cards: Vec<C>,
+}
+
+impl Deck<Card> {
+    /// Creates an unshuffled standard deck of 52 cards, ordered by suit and then by rank.
+    #[must_use]
+    pub fn standard() -> Deck<Card> {
+        let suits = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+        Deck::new(
+            suits
+                .iter()
+                .flat_map(|&suit| (2..=14).map(move |rank| Card { rank, suit }))
+                .collect(),
+        )
+    }
+}
+
+impl<C> Deck<C> {
+    /// Creates a deck of custom cards, the first one being on the top.
+    #[must_use]
+    pub fn new(cards: Vec<C>) -> Deck<C> {
+        Deck { cards }
+    }
+
+    /// Returns the cards left in the deck, from the top.
+    #[must_use]
+    pub fn cards(&self) -> &[C] {
+        &self.cards
+    }
+
+    /// Returns the number of cards left in the deck.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    /// Returns `true` if no card is left in the deck.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// Shuffles the cards left in the deck, returning the permutation drawn for it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `Random::shuffle`, leaving the deck untouched.
+    pub fn shuffle(&mut self, random: &Random) -> Result<Permutation> {
+        random.shuffle(&mut self.cards)
+    }
+
+    /// Deals up to `count` cards from the top of the deck.
+    pub fn deal(&mut self, count: usize) -> Vec<C> {
+        self.cards.drain(..count.min(self.cards.len())).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{flip_coins, roll, Card, Coin, Deck, Dice, DiceTerm, Suit};
+    use crate::test_server::{client, random_result};
+    use crate::Random;
+
+    fn games_server() -> Random {
+        client(|request| {
+            let params = &request["params"];
+            let (min, max) = (
+                params["min"].as_i64().unwrap(),
+                params["max"].as_i64().unwrap(),
+            );
+            let n = params["n"].as_i64().unwrap();
+            let data = if params["replacement"] == true {
+                (0..n)
+                    .map(|i| min + i % (max - min + 1))
+                    .collect::<Vec<i64>>()
+            } else {
+                (min..=max).rev().take(52).collect()
+            };
+            random_result(request, &serde_json::json!(data), 10)
+        })
+    }
+
+    #[test]
+    fn test_dice_notation_parse() {
+        let term = |count, sides, negative| DiceTerm {
+            count,
+            sides,
+            negative,
+        };
+        let dice = " 2d8 + d% - 1D4 - 1 + 3".parse::<Dice>().unwrap();
+        assert_eq!(
+            dice.terms,
+            vec![term(2, 8, false), term(1, 100, false), term(1, 4, true)]
+        );
+        assert_eq!(dice.modifier, 2);
+        for invalid in [
+            "",
+            "5",
+            "d",
+            "0d6",
+            "3d0",
+            "3d6+",
+            "3x6",
+            "2d1000000001",
+            "10001d6",
+            "4000000000d6",
+        ] {
+            assert!(invalid.parse::<Dice>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_games_use_the_service_values() {
+        let random = games_server();
+        let rolled = roll(&random, "3d6+2").unwrap();
+        assert_eq!(rolled.dice, vec![vec![1, 2, 3]]);
+        assert_eq!(rolled.total, 8);
+        assert_eq!(rolled.results[0].bits_used, 10);
+        assert!(roll(&random, "1d6+9223372036854775807").is_err());
+        assert!(roll(&random, "-2d6-9223372036854775807").is_err());
+
+        let flips = flip_coins(&random, 3).unwrap();
+        assert_eq!(flips.coins, vec![Coin::Heads, Coin::Tails, Coin::Heads]);
+        let flips = flip_coins(&random, 0).unwrap();
+        assert!(flips.coins.is_empty() && flips.result.is_none());
+
+        let mut deck = Deck::standard();
+        assert_eq!(deck.len(), 52);
+        let permutation = deck.shuffle(&random).unwrap();
+        assert_eq!(permutation.indices[0], 51);
+        let ace = Card {
+            rank: 14,
+            suit: Suit::Spades,
+        };
+        assert_eq!(deck.deal(2)[0], ace);
+        assert_eq!(ace.to_string(), "A♠");
+        assert_eq!(deck.len(), 50);
+    }
+}
@@ -0,0 +1,282 @@
+//! A pool of random.org api keys with automatic rotation.
+//!
+//! # Usage
+//!
+//! ```rust,no_run
+//! use randomorg::{KeyPool, Random};
+//! use std::time::Duration;
+//!
+//! let pool = KeyPool::new(vec!["FIRST API KEY", "SECOND API KEY"])
+//!     .refresh_interval(Duration::from_secs(600));
+//! let r = Random::with_key_pool(pool);
+//! println!("Result: {:?}", r.generate_integers(-100, 100, 15, true));
+//! println!("Keys: {:?}", r.key_pool().usage());
+//! ```
+
+use crate::{ApiKey, ApiKeyStatus, Error, GetUsageResult, RandomResult, Result};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// A usage of a single key of the `KeyPool` as it was last seen by the client.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct KeyUsage {
+    /// The key status as reported by the last `getUsage` call, if there was any.
+    pub status: Option<ApiKeyStatus>,
+    /// The (estimated) number of remaining true random bits available to the key, if known.
+    pub bits_left: Option<u64>,
+    /// The (estimated) number of remaining API requests available to the key, if known.
+    pub requests_left: Option<u64>,
+    /// Whether the key was rotated away from after the service refused to serve it.
+    pub rotated_out: bool,
+}
+
+impl KeyUsage {
+    fn is_usable(&self) -> bool {
+        !self.rotated_out
+            && matches!(self.status, None | Some(ApiKeyStatus::Running))
+            && self.bits_left != Some(0)
+            && self.requests_left != Some(0)
+    }
+
+    fn rank(&self) -> (u64, u64) {
+        (
+            self.bits_left.unwrap_or(u64::MAX),
+            self.requests_left.unwrap_or(u64::MAX),
+        )
+    }
+}
+
+/// Remaining quota carried by a random.org response.
+pub(crate) trait Quota {
+    /// Returns the key status, if the response carries it.
+    fn status(&self) -> Option<ApiKeyStatus> {
+        None
+    }
+    /// Returns the true random bits used to serve the request.
+    fn bits_used(&self) -> u64 {
+        0
+    }
+    /// Returns the remaining true random bits.
+    fn bits_left(&self) -> u64;
+    /// Returns the remaining requests.
+    fn requests_left(&self) -> u64;
+}
+
+impl<T> Quota for RandomResult<T> {
+    fn bits_used(&self) -> u64 {
+        self.bits_used
+    }
+
+    fn bits_left(&self) -> u64 {
+        self.bits_left
+    }
+
+    fn requests_left(&self) -> u64 {
+        self.requests_left
+    }
+}
+
+impl Quota for GetUsageResult {
+    fn status(&self) -> Option<ApiKeyStatus> {
+        Some(self.status.clone())
+    }
+
+    fn bits_left(&self) -> u64 {
+        self.bits_left
+    }
+
+    fn requests_left(&self) -> u64 {
+        self.requests_left
+    }
+}
+
+#[derive(Debug)]
+struct PooledKey {
+    key: ApiKey,
+    usage: KeyUsage,
+    refreshed_at: Option<Instant>,
+}
+
+/// A set of random.org api keys used by a single `Random` client.
+///
+/// Every request is performed with the key which has the most bits and requests left, as
+/// tracked from the responses of the service. A key which is refused by the service because it
+/// is not running or has exceeded its daily allowance is rotated out until its status is
+/// refreshed with the `getUsage` method.
+#[derive(Debug)]
+pub struct KeyPool {
+    keys: Mutex<Vec<PooledKey>>,
+    refresh_interval: Option<Duration>,
+}
+
+impl KeyPool {
+    /// Creates a pool of the given api keys.
+    pub fn new<I, S>(keys: I) -> KeyPool
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<ApiKey>,
+    {
+        KeyPool {
+            keys: Mutex::new(
+                keys.into_iter()
+                    .map(|key| PooledKey {
+                        key: key.into(),
+                        usage: KeyUsage {
+                            status: None,
+                            bits_left: None,
+                            requests_left: None,
+                            rotated_out: false,
+                        },
+                        refreshed_at: None,
+                    })
+                    .collect(),
+            ),
+            refresh_interval: None,
+        }
+    }
+
+    /// Sets how often the status of every key is refreshed with the `getUsage` method.
+    /// By default the keys are refreshed only when none of them may be used.
+    #[must_use]
+    pub fn refresh_interval(mut self, refresh_interval: Duration) -> Self {
+        self.refresh_interval = Some(refresh_interval);
+        self
+    }
+
+    /// Returns the usage of every key of the pool, in the order the keys were given.
+    pub fn usage(&self) -> Vec<KeyUsage> {
+        self.lock().iter().map(|k| k.usage.clone()).collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<PooledKey>> {
+        self.keys.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the usable key with the most bits and requests left.
+    pub(crate) fn pick(&self) -> Result<ApiKey> {
+        self.lock()
+            .iter()
+            .filter(|k| k.usage.is_usable())
+            .max_by_key(|k| k.usage.rank())
+            .map(|k| k.key.clone())
+            .ok_or(Error::NoUsableKey)
+    }
+
+    /// Returns the known bits left to the key.
+    pub(crate) fn bits_left(&self, key: &ApiKey) -> Option<u64> {
+        self.lock()
+            .iter()
+            .find(|k| k.key == *key)
+            .and_then(|k| k.usage.bits_left)
+    }
+
+    /// Returns the total of the known bits and requests left to the keys.
+    pub(crate) fn left(&self) -> (Option<u64>, Option<u64>) {
+        let keys = self.lock();
+        let sum = |left: fn(&KeyUsage) -> Option<u64>| {
+            keys.iter()
+                .filter_map(|k| left(&k.usage))
+                .fold(None, |total: Option<u64>, left| {
+                    Some(total.unwrap_or(0).saturating_add(left))
+                })
+        };
+        (sum(|u| u.bits_left), sum(|u| u.requests_left))
+    }
+
+    /// Returns all the keys of the pool.
+    pub(crate) fn keys(&self) -> Vec<ApiKey> {
+        self.lock().iter().map(|k| k.key.clone()).collect()
+    }
+
+    /// Returns the keys whose status has not been refreshed for the refresh interval.
+    pub(crate) fn stale_keys(&self) -> Vec<ApiKey> {
+        let Some(refresh_interval) = self.refresh_interval else {
+            return Vec::new();
+        };
+        self.lock()
+            .iter()
+            .filter(|k| {
+                k.refreshed_at
+                    .map_or(true, |t| t.elapsed() >= refresh_interval)
+            })
+            .map(|k| k.key.clone())
+            .collect()
+    }
+
+    /// Records the quota reported by the service for the key.
+    pub(crate) fn record<Q: Quota>(&self, key: &ApiKey, quota: &Q) {
+        if let Some(k) = self.lock().iter_mut().find(|k| k.key == *key) {
+            if let Some(status) = quota.status() {
+                k.usage.status = Some(status);
+                k.usage.rotated_out = false;
+                k.refreshed_at = Some(Instant::now());
+            }
+            k.usage.bits_left = Some(quota.bits_left());
+            k.usage.requests_left = Some(quota.requests_left());
+        }
+    }
+
+    /// Rotates the key out of the pool until its status is refreshed.
+    pub(crate) fn rotate_out(&self, key: &ApiKey, refreshed: bool) {
+        if let Some(k) = self.lock().iter_mut().find(|k| k.key == *key) {
+            k.usage.rotated_out = true;
+            if refreshed {
+                k.refreshed_at = Some(Instant::now());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KeyPool, Quota};
+    use crate::{ApiKey, ApiKeyStatus, Error};
+
+    struct Left(u64, u64, Option<ApiKeyStatus>);
+
+    impl Quota for Left {
+        fn status(&self) -> Option<ApiKeyStatus> {
+            self.2.clone()
+        }
+
+        fn bits_left(&self) -> u64 {
+            self.0
+        }
+
+        fn requests_left(&self) -> u64 {
+            self.1
+        }
+    }
+
+    fn key(s: &str) -> ApiKey {
+        ApiKey::from(s)
+    }
+
+    #[test]
+    fn test_picks_key_with_most_quota_left() {
+        let pool = KeyPool::new(vec!["a", "b", "c"]);
+        pool.record(&key("a"), &Left(1000, 10, None));
+        pool.record(&key("b"), &Left(5000, 10, None));
+        pool.record(&key("c"), &Left(5000, 0, None));
+
+        assert_eq!(pool.pick().unwrap(), key("b"));
+    }
+
+    #[test]
+    fn test_rotates_out_and_back_in() {
+        let pool = KeyPool::new(vec!["a", "b"]);
+        pool.record(&key("a"), &Left(1000, 10, None));
+        pool.record(&key("b"), &Left(10, 10, None));
+
+        pool.rotate_out(&key("a"), false);
+        assert_eq!(pool.pick().unwrap(), key("b"));
+        pool.rotate_out(&key("b"), false);
+        assert!(matches!(pool.pick(), Err(Error::NoUsableKey)));
+
+        pool.record(&key("a"), &Left(900, 9, Some(ApiKeyStatus::Running)));
+        assert_eq!(pool.pick().unwrap(), key("a"));
+
+        pool.record(&key("a"), &Left(900, 9, Some(ApiKeyStatus::Paused)));
+        assert!(matches!(pool.pick(), Err(Error::NoUsableKey)));
+    }
+}
@@ -18,14 +18,12 @@
 //! }
 //! ```
 //!
-//! # Warning
+//! # Concurrency
 //!
-//! However the library provides a thread-safe object, the **random.org** service does not allow
-//! to use their API from multithreaded-apps. The service processes incoming calls according the
-//! `request_id` parameter, it must be different for concurrent calls, while this library always
-//! provides `request_id` equal to `1`. So when you use the library from multiple threads the
-//! library will send multiple requests simultaneously with the same `request_id` field and the
-//! **random.org** service will refuse to process them.
+//! The **random.org** service processes incoming calls according to the `request_id` parameter,
+//! which must be different for concurrent calls. The library gives every request an id of its
+//! own, so the client may be used from several threads, and the streams may prefetch their
+//! next page while the other calls are made.
 
 #![deny(clippy::all)]
 #![deny(clippy::pedantic)]
@@ -34,18 +32,40 @@
 
 mod date_de;
 mod error;
+pub mod games;
+mod key_pool;
+pub mod lottery;
 mod methods;
 mod model;
 mod params;
+pub mod passwords;
 #[cfg(feature = "rng")]
 pub mod rand;
+mod reader;
 mod request_builders;
 mod requests;
 mod results;
+mod sampling;
+mod stream;
+#[cfg(test)]
+mod test_server;
+mod usage;
 pub mod version;
+mod weighted;
 
+use key_pool::Quota;
+pub use key_pool::{KeyPool, KeyUsage};
 use methods::Method;
-pub use model::{AllowedCharacters, ApiKey, ApiKeyStatus, Request, RequestId, Response};
+use model::RawResponse;
+pub use model::{
+    AllowedCharacters, ApiKey, ApiKeyStatus, ExactDecimal, Request, RequestId, Response,
+};
+use params::{check_gaussians, EstimatedBits};
+pub use params::{
+    ApiKeyParams, GenerateBlobsParams, GenerateDecimalFractionsParams, GenerateGaussiansParams,
+    GenerateIntegersParams, GenerateStringsParams, GenerateUUIDsParams,
+};
+pub use reader::RandomReader;
 pub use request_builders::{
     RequestBlobs, RequestDecimalFractions, RequestGaussians, RequestIntegers, RequestStrings,
     RequestUUIDs,
@@ -55,10 +75,15 @@ use requests::{
     GenerateIntegersRequest, GenerateStringsRequest, GenerateUUIDsRequest,
 };
 pub use results::{
-    GenerateBlobsResult, GenerateDecimalFractionsResult, GenerateGaussiansResult,
-    GenerateIntegersResult, GenerateStringsResult, GenerateUUIDsResult, GetUsageResult, RandomData,
-    RandomResult,
+    GenerateBlobsResult, GenerateDecimalFractionsExactResult, GenerateDecimalFractionsResult,
+    GenerateGaussiansExactResult, GenerateGaussiansResult, GenerateIntegersResult,
+    GenerateStringsResult, GenerateUUIDsResult, GetUsageResult, RandomData, RandomResult,
 };
+pub use sampling::Permutation;
+pub use stream::RandomStream;
+use usage::UsageTracker;
+pub use usage::{Budget, UsageSnapshot};
+pub use weighted::{ChooseWeighted, Weight, WeightedPicks};
 
 pub use error::{Error, ErrorCode, ResponseError, Result};
 
@@ -68,23 +93,17 @@ const API_INVOKE: &str = "https://api.random.org/json-rpc/2/invoke";
 /// Performs a request.
 macro_rules! make_request {
     ($api:ident, $body:expr) => {{
-        $api.client.post(API_INVOKE).json($body).send()
+        $api.client.post($api.endpoint.as_str()).json($body).send()
     }};
 }
 
-/// Macro only for internal use with the `Random` object (relies on it's fields)
-/// Parses a service data from the request's response.
-macro_rules! request {
-    ($api:ident, $request:ident) => {
-        Ok(make_request!($api, &$request)?.json()?)
-    };
-}
-
 /// A random.org api client.
 #[derive(Debug, Clone)]
 pub struct Random {
     client: reqwest::blocking::Client,
-    api_key: ApiKey,
+    endpoint: String,
+    keys: std::sync::Arc<KeyPool>,
+    usage: std::sync::Arc<std::sync::Mutex<UsageTracker>>,
 }
 
 impl Random {
@@ -100,13 +119,192 @@ impl Random {
     ///     let r = Random::new("API KEY HERE");
     /// }
     /// ```
-    pub fn new<S: Into<String>>(api_key: S) -> Random {
+    ///
+    /// The key may also be read from the environment or from a file, keeping it out of the
+    /// source code:
+    ///
+    /// ```rust,no_run
+    /// extern crate randomorg;
+    ///
+    /// fn main() {
+    ///     use randomorg::{ApiKey, Random};
+    ///     let r = Random::new(ApiKey::from_env("RANDOM_ORG_API_KEY").unwrap());
+    /// }
+    /// ```
+    pub fn new<S: Into<ApiKey>>(api_key: S) -> Random {
+        Random::with_key_pool(KeyPool::new(std::iter::once(api_key)))
+    }
+
+    /// Creates new random.org client which rotates between the keys of the pool.
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// extern crate randomorg;
+    ///
+    /// fn main() {
+    ///     use randomorg::{KeyPool, Random};
+    ///     let r = Random::with_key_pool(KeyPool::new(vec!["API KEY", "ANOTHER API KEY"]));
+    /// }
+    /// ```
+    pub fn with_key_pool(keys: KeyPool) -> Random {
         Random {
             client: reqwest::blocking::Client::new(),
-            api_key: ApiKey(api_key.into()),
+            endpoint: API_INVOKE.to_owned(),
+            keys: std::sync::Arc::new(keys),
+            usage: std::sync::Arc::default(),
         }
     }
 
+    /// Sets the budget the client must keep to. Requests which would break it are refused
+    /// with `Error::BudgetExceeded` before being sent. A request which fails once it was sent
+    /// counts its estimated bits, as the service may have spent them. The budget is shared with
+    /// the clones of the client.
+    ///
+    /// # Usage
+    ///
+    /// ```rust
+    /// extern crate randomorg;
+    ///
+    /// fn main() {
+    ///     use randomorg::{Budget, Random};
+    ///     use std::time::Duration;
+    ///     let budget = Budget::new(Duration::from_secs(60)).max_requests(10);
+    ///     let r = Random::new("API KEY HERE").with_budget(budget);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn with_budget(self, budget: Budget) -> Random {
+        self.tracker().set_budget(budget);
+        self
+    }
+
+    /// Returns the quota usage of the client, as tracked from the responses of the service.
+    #[must_use]
+    pub fn usage_snapshot(&self) -> UsageSnapshot {
+        let (bits_left, requests_left) = self.keys.left();
+        self.tracker().snapshot(bits_left, requests_left)
+    }
+
+    /// Sends the requests of the client to another endpoint, such as a fake service.
+    #[cfg(test)]
+    pub(crate) fn with_endpoint(mut self, endpoint: String) -> Random {
+        self.endpoint = endpoint;
+        self
+    }
+
+    /// Checks that `requests` calls expected to use `estimated_bits` in total fit into the
+    /// budget, before the first of them is made.
+    fn check_budget(&self, estimated_bits: u64, requests: u64) -> Result<()> {
+        let (bits_left, _) = self.keys.left();
+        self.tracker().check(estimated_bits, requests, bits_left)
+    }
+
+    fn tracker(&self) -> std::sync::MutexGuard<'_, UsageTracker> {
+        self.usage
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Returns the pool of the api keys used by the client.
+    #[must_use]
+    pub fn key_pool(&self) -> &KeyPool {
+        &self.keys
+    }
+
+    /// Refreshes the status of every key of the pool with the `getUsage` method, bringing the
+    /// keys which were rotated out back in once the service is ready to serve them again.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first `getUsage` call which fails for another reason than the
+    /// service refusing the key.
+    pub fn refresh_key_pool(&self) -> Result<()> {
+        self.refresh_keys(self.keys.keys())
+    }
+
+    fn refresh_keys(&self, keys: Vec<ApiKey>) -> Result<()> {
+        for api_key in keys {
+            let request = EmptyRequest::new(Method::GetUsage, api_key.clone());
+            match self.send::<_, GetUsageResult>(&request) {
+                Ok(response) => self.keys.record(&api_key, &response.result),
+                Err(Error::RandomOrg(_, ref e)) if e.code.is_key_unusable() => {
+                    self.keys.rotate_out(&api_key, true);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Performs a request with the best key of the pool, rotating to another key when the
+    /// service refuses to serve the chosen one.
+    fn invoke<P, R, F>(&self, request: F) -> Result<Response<R>>
+    where
+        P: serde::Serialize + EstimatedBits,
+        R: serde::de::DeserializeOwned + Quota,
+        F: Fn(ApiKey) -> Request<P>,
+    {
+        self.refresh_keys(self.keys.stale_keys())?;
+        let mut refreshed = false;
+        let mut last_error = None;
+        loop {
+            let api_key = match self.keys.pick() {
+                Ok(api_key) => api_key,
+                Err(_) if !refreshed => {
+                    refreshed = true;
+                    self.refresh_key_pool()?;
+                    continue;
+                }
+                Err(e) => return Err(last_error.unwrap_or(e)),
+            };
+            let request = request(api_key.clone());
+            let billed = !matches!(request.method, Method::GetUsage);
+            let estimated_bits = request.params.estimated_bits();
+            if billed {
+                self.tracker()
+                    .reserve(estimated_bits, self.keys.bits_left(&api_key))?;
+            }
+            let result = self.send::<P, R>(&request);
+            if billed {
+                match result {
+                    Ok(ref response) => self
+                        .tracker()
+                        .record(estimated_bits, response.result.bits_used()),
+                    // The service may have spent the bits of a request which reached it.
+                    Err(ref e) if !is_unsent(e) => {
+                        self.tracker().record(estimated_bits, estimated_bits);
+                    }
+                    Err(_) => self.tracker().release(estimated_bits),
+                }
+            }
+            match result {
+                Ok(response) => {
+                    self.keys.record(&api_key, &response.result);
+                    return Ok(response);
+                }
+                Err(Error::RandomOrg(status, e)) if e.code.is_key_unusable() => {
+                    self.keys.rotate_out(&api_key, false);
+                    last_error = Some(Error::RandomOrg(status, e));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn send<P, R>(&self, request: &Request<P>) -> Result<Response<R>>
+    where
+        P: serde::Serialize,
+        R: serde::de::DeserializeOwned,
+    {
+        let response = make_request!(self, request)?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(response.into());
+        }
+        response.json::<RawResponse<R>>()?.into_result(status)
+    }
+
     /// Create a request object for generating random integers
     ///
     /// # Usage
@@ -121,7 +319,8 @@ impl Random {
     ///     println!("Random integers: {:?}", random_data);
     /// }
     /// ```
-    pub fn request_integers(&self) -> RequestIntegers {
+    #[must_use]
+    pub fn request_integers(&self) -> RequestIntegers<'_> {
         RequestIntegers::new(self)
     }
 
@@ -137,11 +336,12 @@ impl Random {
     ///     let r = Random::new("API KEY HERE");
     ///     let random_data = r.request_decimal_fractions().limit(5)
     ///                                                    .decimal_places(4)
-    ///                                                    .collect::<Vec<f32>>();
+    ///                                                    .collect::<Vec<f64>>();
     ///     println!("Random decimal fractions: {:?}", random_data);
     /// }
     /// ```
-    pub fn request_decimal_fractions(&self) -> RequestDecimalFractions {
+    #[must_use]
+    pub fn request_decimal_fractions(&self) -> RequestDecimalFractions<'_> {
         RequestDecimalFractions::new(self)
     }
 
@@ -156,11 +356,12 @@ impl Random {
     ///     use randomorg::Random;
     ///     let r = Random::new("API KEY HERE");
     ///     let random_data = r.request_gaussians().limit(5)
-    ///                                            .collect::<Vec<f32>>();
+    ///                                            .collect::<Vec<f64>>();
     ///     println!("Random gaussians: {:?}", random_data);
     /// }
     /// ```
-    pub fn request_gaussians(&self) -> RequestGaussians {
+    #[must_use]
+    pub fn request_gaussians(&self) -> RequestGaussians<'_> {
         RequestGaussians::new(self)
     }
 
@@ -179,7 +380,8 @@ impl Random {
     ///     println!("Random strings: {:?}", random_data);
     /// }
     /// ```
-    pub fn request_strings(&self) -> RequestStrings {
+    #[must_use]
+    pub fn request_strings(&self) -> RequestStrings<'_> {
         RequestStrings::new(self)
     }
 
@@ -198,7 +400,8 @@ impl Random {
     ///     println!("Random strings: {:?}", random_data);
     /// }
     /// ```
-    pub fn request_uuids(&self) -> RequestUUIDs {
+    #[must_use]
+    pub fn request_uuids(&self) -> RequestUUIDs<'_> {
         RequestUUIDs::new(self)
     }
 
@@ -217,7 +420,8 @@ impl Random {
     ///     println!("Random strings: {:?}", random_data);
     /// }
     /// ```
-    pub fn request_blobs(&self) -> RequestBlobs {
+    #[must_use]
+    pub fn request_blobs(&self) -> RequestBlobs<'_> {
         RequestBlobs::new(self)
     }
 
@@ -241,6 +445,12 @@ impl Random {
     /// * `min` must be within [-1e9; 1e9] range
     /// * `max` must be within [-1e9; 1e9] range
     /// * `limit` must be within [1; 1e4] range
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::BudgetExceeded` if the request would break the budget of the client,
+    /// `Error::RandomOrg` if the service refuses it, and the error of the transport or of the
+    /// decoding otherwise.
     pub fn generate_integers(
         &self,
         min: i32,
@@ -248,9 +458,7 @@ impl Random {
         limit: u16,
         replacement: bool,
     ) -> Result<Response<GenerateIntegersResult>> {
-        let request =
-            GenerateIntegersRequest::new(self.api_key.clone(), min, max, limit, replacement);
-        request!(self, request)
+        self.invoke(|api_key| GenerateIntegersRequest::new(api_key, min, max, limit, replacement))
     }
 
     /// This method generates true random decimal fractions from a uniform distribution across
@@ -273,14 +481,46 @@ impl Random {
     /// # Constraints
     /// * `limit` must be within [1; 1e4] range
     /// * `decimal_places` must be within [1; 20] range
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::BudgetExceeded` if the request would break the budget of the client,
+    /// `Error::RandomOrg` if the service refuses it, and the error of the transport or of the
+    /// decoding otherwise.
     pub fn generate_decimal_fractions(
         &self,
         limit: u16,
         decimal_places: u8,
     ) -> Result<Response<GenerateDecimalFractionsResult>> {
-        let request =
-            GenerateDecimalFractionsRequest::new(self.api_key.clone(), limit, decimal_places);
-        request!(self, request)
+        self.invoke(|api_key| GenerateDecimalFractionsRequest::new(api_key, limit, decimal_places))
+    }
+
+    /// Same as `generate_decimal_fractions`, but keeps every digit of the decimal fractions the
+    /// service returned instead of rounding them to `f64`.
+    ///
+    /// # Usage
+    ///
+    /// ```rust,no_run
+    /// extern crate randomorg;
+    ///
+    /// fn main() {
+    ///     use randomorg::Random;
+    ///     let r = Random::new("API KEY HERE");
+    ///     println!("Result: {:?}", r.generate_decimal_fractions_exact(10, 20));
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::BudgetExceeded` if the request would break the budget of the client,
+    /// `Error::RandomOrg` if the service refuses it, and the error of the transport or of the
+    /// decoding otherwise.
+    pub fn generate_decimal_fractions_exact(
+        &self,
+        limit: u16,
+        decimal_places: u8,
+    ) -> Result<Response<GenerateDecimalFractionsExactResult>> {
+        self.invoke(|api_key| GenerateDecimalFractionsRequest::new(api_key, limit, decimal_places))
     }
 
     /// This method generates true random numbers from a Gaussian distribution (also known as a
@@ -297,7 +537,7 @@ impl Random {
     /// fn main() {
     ///     use randomorg::Random;
     ///     let r = Random::new("API KEY HERE");
-    ///     println!("Result: {:?}", r.generate_gaussians(2000, 1100, 100, 4));
+    ///     println!("Result: {:?}", r.generate_gaussians(2000, 0.5, 0.01, 4));
     /// }
     /// ```
     ///
@@ -306,21 +546,73 @@ impl Random {
     /// * `mean` must be within [-1e6; 1e6] range
     /// * `standard_deviation` must be within [-1e6; 1e6] range
     /// * `significant_digits` must be within [2; 20] range
-    pub fn generate_gaussians(
+    ///
+    /// The mean and the standard deviation are real numbers; integers are accepted as well.
+    /// Values outside of their range are refused with `Error::Rest` before any request is made.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::BudgetExceeded` if the request would break the budget of the client,
+    /// `Error::RandomOrg` if the service refuses it, and the error of the transport or of the
+    /// decoding otherwise.
+    pub fn generate_gaussians<M: Into<f64>, D: Into<f64>>(
         &self,
         limit: u16,
-        mean: i32,
-        standard_deviation: i32,
+        mean: M,
+        standard_deviation: D,
         significant_digits: u8,
     ) -> Result<Response<GenerateGaussiansResult>> {
-        let request = GenerateGaussiansRequest::new(
-            self.api_key.clone(),
-            limit,
-            mean,
-            standard_deviation,
-            significant_digits,
-        );
-        request!(self, request)
+        let (mean, standard_deviation) = (mean.into(), standard_deviation.into());
+        check_gaussians(mean, standard_deviation)?;
+        self.invoke(|api_key| {
+            GenerateGaussiansRequest::new(
+                api_key,
+                limit,
+                mean,
+                standard_deviation,
+                significant_digits,
+            )
+        })
+    }
+
+    /// Same as `generate_gaussians`, but keeps every digit of the gaussians the service
+    /// returned instead of rounding them to `f64`.
+    ///
+    /// # Usage
+    ///
+    /// ```rust,no_run
+    /// extern crate randomorg;
+    ///
+    /// fn main() {
+    ///     use randomorg::Random;
+    ///     let r = Random::new("API KEY HERE");
+    ///     println!("Result: {:?}", r.generate_gaussians_exact(2000, 1100, 100, 20));
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::BudgetExceeded` if the request would break the budget of the client,
+    /// `Error::RandomOrg` if the service refuses it, and the error of the transport or of the
+    /// decoding otherwise.
+    pub fn generate_gaussians_exact<M: Into<f64>, D: Into<f64>>(
+        &self,
+        limit: u16,
+        mean: M,
+        standard_deviation: D,
+        significant_digits: u8,
+    ) -> Result<Response<GenerateGaussiansExactResult>> {
+        let (mean, standard_deviation) = (mean.into(), standard_deviation.into());
+        check_gaussians(mean, standard_deviation)?;
+        self.invoke(|api_key| {
+            GenerateGaussiansRequest::new(
+                api_key,
+                limit,
+                mean,
+                standard_deviation,
+                significant_digits,
+            )
+        })
     }
 
     /// This method generates true random strings.
@@ -345,17 +637,23 @@ impl Random {
     /// * `limit` must be within [1; 1e4] range
     /// * `length` must be within [1; 20] range
     /// * `characters` must contain maximum 80 characters.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::BudgetExceeded` if the request would break the budget of the client,
+    /// `Error::RandomOrg` if the service refuses it, and the error of the transport or of the
+    /// decoding otherwise.
+    #[allow(clippy::needless_pass_by_value)]
     pub fn generate_strings(
         &self,
         limit: u16,
         length: u8,
         characters: AllowedCharacters,
     ) -> Result<Response<GenerateStringsResult>> {
-        let request = GenerateStringsRequest::new(self.api_key.clone(), limit, length, characters);
-        request!(self, request)
+        self.invoke(|api_key| GenerateStringsRequest::new(api_key, limit, length, &characters))
     }
 
-    /// This method generates version 4 true random Universally Unique IDentifiers (UUIDs) in
+    /// This method generates version 4 true random Universally Unique Identifiers (UUIDs) in
     /// accordance with section 4.4 of RFC 4122.
     ///
     /// * [Official documentation](https://api.random.org/json-rpc/2/basic#generateUUIDs)
@@ -374,12 +672,17 @@ impl Random {
     ///
     /// # Constraints
     /// * `limit` must be within [1; 1e3] range
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::BudgetExceeded` if the request would break the budget of the client,
+    /// `Error::RandomOrg` if the service refuses it, and the error of the transport or of the
+    /// decoding otherwise.
     pub fn generate_uuids(&self, limit: u16) -> Result<Response<GenerateUUIDsResult>> {
-        let request = GenerateUUIDsRequest::new(self.api_key.clone(), limit);
-        request!(self, request)
+        self.invoke(|api_key| GenerateUUIDsRequest::new(api_key, limit))
     }
 
-    /// This method generates Binary Large OBjects (BLOBs) containing true random data.
+    /// This method generates Binary Large Objects (BLOBs) containing true random data.
     ///
     /// * [Official documentation](https://api.random.org/json-rpc/2/basic#generateBlobs)
     ///
@@ -398,9 +701,14 @@ impl Random {
     /// # Constraints
     /// * `limit` must be within [1; 100] range
     /// * `size` must be within [1, 1048576] range
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::BudgetExceeded` if the request would break the budget of the client,
+    /// `Error::RandomOrg` if the service refuses it, and the error of the transport or of the
+    /// decoding otherwise.
     pub fn generate_blobs(&self, limit: u16, size: u32) -> Result<Response<GenerateBlobsResult>> {
-        let request = GenerateBlobsRequest::new(self.api_key.clone(), limit, size);
-        request!(self, request)
+        self.invoke(|api_key| GenerateBlobsRequest::new(api_key, limit, size))
     }
 
     /// Returns information related to the usage of a given API key.
@@ -418,18 +726,213 @@ impl Random {
     ///     println!("Result: {:?}", r.get_usage());
     /// }
     /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::RandomOrg` if the service refuses the key, and the error of the transport or
+    /// of the decoding otherwise.
     pub fn get_usage(&self) -> Result<Response<GetUsageResult>> {
-        let request = EmptyRequest::new(Method::GetUsage, self.api_key.clone());
-        request!(self, request)
+        self.invoke(|api_key| EmptyRequest::new(Method::GetUsage, api_key))
     }
 }
 
+/// Returns `true` if the request failed before it was sent to the service.
+fn is_unsent(error: &Error) -> bool {
+    matches!(error, Error::Reqwest(e) if e.is_builder() || e.is_connect())
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::test_server::{client, error_result, random_result, serve};
+    use crate::{Budget, Error, KeyPool, Random};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
     fn assert_sync_and_send<T: Sync + Send>() {}
 
     #[test]
     fn test_sync_and_send() {
         assert_sync_and_send::<crate::Random>();
     }
+
+    /// Builds a `getUsage` response to `request` of a running key with `bits_left` bits.
+    fn usage_result(request: &serde_json::Value, bits_left: u64) -> serde_json::Value {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "result": {
+                "status": "running",
+                "creationTime": "2017-06-22 13:32:16Z",
+                "bitsLeft": bits_left,
+                "requestsLeft": 1000,
+                "totalBits": 0,
+                "totalRequests": 0
+            },
+            "id": request["id"]
+        })
+    }
+
+    /// Starts a fake service which refuses the `spent` keys with the error 402 and logs the
+    /// method and the key of every call.
+    fn keys_server(spent: Arc<Mutex<Vec<&'static str>>>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let logged = Arc::clone(&calls);
+        let endpoint = serve(move |request| {
+            let method = request["method"].as_str().unwrap();
+            let key = request["params"]["apiKey"].as_str().unwrap();
+            logged.lock().unwrap().push(format!("{method} {key}"));
+            if method == "getUsage" {
+                usage_result(request, 1000)
+            } else if spent.lock().unwrap().contains(&key) {
+                error_result(request, 402, "The daily request allowance is exceeded.")
+            } else {
+                random_result(request, &serde_json::json!([1, 2]), 14)
+            }
+        });
+        (endpoint, calls)
+    }
+
+    #[test]
+    fn test_invoke_rotates_unusable_keys_out() {
+        let spent = Arc::new(Mutex::new(vec!["spent"]));
+        let (endpoint, calls) = keys_server(Arc::clone(&spent));
+        // Neither key has a known quota, so the last one is picked first.
+        let random =
+            Random::with_key_pool(KeyPool::new(vec!["good", "spent"])).with_endpoint(endpoint);
+
+        let data = random.generate_integers(1, 100, 2, true).unwrap();
+        assert_eq!(data.result.random.data, vec![1, 2]);
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec!["generateIntegers spent", "generateIntegers good"]
+        );
+        let usage = random.key_pool().usage();
+        assert!(!usage[0].rotated_out && usage[1].rotated_out);
+        assert_eq!(usage[0].bits_left, Some(1_000_000_000 - 14));
+
+        // Once every key is refused, the pool is refreshed a single time before giving up.
+        spent.lock().unwrap().push("good");
+        calls.lock().unwrap().clear();
+        assert!(matches!(
+            random.generate_integers(1, 100, 2, true),
+            Err(Error::RandomOrg(_, ref e)) if e.code.0 == 402
+        ));
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                "generateIntegers good",
+                "getUsage good",
+                "getUsage spent",
+                "generateIntegers spent",
+                "generateIntegers good",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_key_pool_refreshes_stale_keys() {
+        let (endpoint, calls) = keys_server(Arc::default());
+        let pool =
+            KeyPool::new(vec!["first", "second"]).refresh_interval(Duration::from_millis(500));
+        let random = Random::with_key_pool(pool).with_endpoint(endpoint);
+
+        // The keys were never refreshed, so they are stale before the first call.
+        random.generate_integers(1, 100, 2, true).unwrap();
+        random.generate_integers(1, 100, 2, true).unwrap();
+        let refreshes = || {
+            calls
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|call| call.starts_with("getUsage"))
+                .count()
+        };
+        assert_eq!(refreshes(), 2);
+
+        std::thread::sleep(Duration::from_millis(500));
+        random.generate_integers(1, 100, 2, true).unwrap();
+        assert_eq!(refreshes(), 4);
+
+        // Without a refresh interval, the keys are refreshed only when none may be used.
+        let (endpoint, calls) = keys_server(Arc::default());
+        let random = Random::new("key").with_endpoint(endpoint);
+        random.generate_integers(1, 100, 2, true).unwrap();
+        assert_eq!(*calls.lock().unwrap(), vec!["generateIntegers key"]);
+    }
+
+    #[test]
+    fn test_budget_refuses_calls_before_sending_them() {
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counted = Arc::clone(&calls);
+        let random = client(move |request| {
+            counted.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if request["params"]["n"] == 3 {
+                serde_json::json!({ "jsonrpc": "2.0", "result": "garbage", "id": request["id"] })
+            } else {
+                random_result(request, &serde_json::json!([1, 2]), 8)
+            }
+        })
+        .with_budget(Budget::new(Duration::from_secs(60)).max_bits(40));
+
+        // An integer of 256 values uses 8 bits, so 6 of them would exceed the 40 bits.
+        assert!(matches!(
+            random.generate_integers(0, 255, 6, true),
+            Err(Error::BudgetExceeded(_))
+        ));
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 0);
+        random.generate_integers(0, 255, 2, true).unwrap();
+        assert_eq!(random.usage_snapshot().window_bits_used, 8);
+
+        // The service may have spent the bits of a call whose response can't be decoded.
+        assert!(matches!(
+            random.generate_integers(0, 255, 3, true),
+            Err(Error::Reqwest(_))
+        ));
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+        assert_eq!(random.usage_snapshot().window_bits_used, 32);
+        assert!(matches!(
+            random.generate_integers(0, 255, 2, true),
+            Err(Error::BudgetExceeded(_))
+        ));
+
+        // Nothing listens on a port which was just released, so no bits are spent.
+        let closed = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/", closed.local_addr().unwrap());
+        drop(closed);
+        let random = Random::new("API KEY")
+            .with_endpoint(endpoint)
+            .with_budget(Budget::new(Duration::from_secs(60)).max_bits(16));
+        assert!(matches!(
+            random.generate_integers(0, 255, 2, true),
+            Err(Error::Reqwest(_))
+        ));
+        assert_eq!(random.usage_snapshot().requests_used, 0);
+        assert!(matches!(
+            random.generate_integers(0, 255, 2, true),
+            Err(Error::Reqwest(_))
+        ));
+    }
+
+    #[test]
+    fn test_concurrent_requests_have_their_own_ids() {
+        let ids = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&ids);
+        let random = client(move |request| {
+            seen.lock().unwrap().push(request["id"].as_u64().unwrap());
+            random_result(request, &serde_json::json!([1]), 7)
+        });
+
+        let threads = (0..4)
+            .map(|_| {
+                let random = random.clone();
+                std::thread::spawn(move || random.generate_integers(1, 100, 1, true).unwrap())
+            })
+            .collect::<Vec<_>>();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        let mut ids = ids.lock().unwrap().clone();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), 4);
+    }
 }
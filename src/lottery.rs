@@ -0,0 +1,205 @@
+//! Lottery draws: unique main numbers and bonus balls from a separate pool, drawn as unique
+//! integers by random.org.
+//!
+//! The draw keeps the results of the service the numbers come from, with the numbers in the
+//! order they were drawn. The crate does not implement the signed api methods, so the draw
+//! carries no signature or serial number.
+//!
+//! # Usage
+//!
+//! ```rust,no_run
+//! use randomorg::lottery::Lottery;
+//! use randomorg::Random;
+//!
+//! let random = Random::new("API KEY HERE");
+//! // 5 numbers out of 50 and 2 bonus balls out of 12.
+//! let draw = Lottery::new(5, 50).bonus(2, 12).draw(&random).unwrap();
+//! println!("Numbers: {:?}, bonus: {:?}", draw.numbers, draw.bonus);
+//! ```
+
+use crate::{Error, GenerateIntegersResult, Random, Response, Result};
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+
+/// The largest pool of numbers: the service generates integers up to 1e9.
+const MAX_POOL: u32 = 1_000_000_000;
+
+/// The rules of a lottery: how many numbers are picked out of how large pools.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Lottery {
+    picks: u32,
+    pool: u32,
+    bonus_picks: u32,
+    bonus_pool: u32,
+}
+
+/// The outcome of a lottery draw.
+#[derive(Debug, Clone)]
+pub struct LotteryDraw {
+    /// The main numbers, in ascending order.
+    pub numbers: Vec<u32>,
+    /// The bonus balls, in the order they were drawn.
+    pub bonus: Vec<u32>,
+    /// The result of the service the main numbers come from, in the order they were drawn.
+    pub numbers_result: GenerateIntegersResult,
+    /// The result of the service the bonus balls come from. It is `None` when the lottery has
+    /// no bonus balls.
+    pub bonus_result: Option<GenerateIntegersResult>,
+}
+
+impl Lottery {
+    /// Creates a lottery picking `picks` unique numbers out of the `1..=pool` range.
+    #[must_use]
+    pub fn new(picks: u32, pool: u32) -> Lottery {
+        Lottery {
+            picks,
+            pool,
+            bonus_picks: 0,
+            bonus_pool: 0,
+        }
+    }
+
+    /// A builder method. Adds `picks` unique bonus balls out of the separate `1..=pool` range.
+    #[must_use]
+    pub fn bonus(mut self, picks: u32, pool: u32) -> Self {
+        self.bonus_picks = picks;
+        self.bonus_pool = pool;
+        self
+    }
+
+    /// Draws the numbers (performs the requests): a call for the main numbers and another one
+    /// for the bonus balls.
+    ///
+    /// # Constraints
+    /// * at least one main number must be picked
+    /// * the numbers picked from a pool must not outnumber it
+    /// * a pool must be within [1; 1e9] range
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Rest` if a constraint is broken, before any call is made, and the error of
+    /// the service if a call fails.
+    pub fn draw(&self, random: &Random) -> Result<LotteryDraw> {
+        if self.picks == 0 {
+            return Err(Error::Rest("A lottery must draw at least one number."));
+        }
+        let max = check_pool(self.picks, self.pool)?;
+        let bonus_max = if self.bonus_picks == 0 {
+            None
+        } else {
+            Some(check_pool(self.bonus_picks, self.bonus_pool)?)
+        };
+        let numbers_result = draw_unique(random, self.picks, max)?;
+        let bonus_result = match bonus_max {
+            Some(max) => Some(draw_unique(random, self.bonus_picks, max)?),
+            None => None,
+        };
+        let mut numbers = drawn_numbers(&numbers_result, self.picks, self.pool)?;
+        numbers.sort_unstable();
+        let bonus = bonus_result.as_ref().map_or(Ok(Vec::new()), |result| {
+            drawn_numbers(result, self.bonus_picks, self.bonus_pool)
+        })?;
+        Ok(LotteryDraw {
+            numbers,
+            bonus,
+            numbers_result,
+            bonus_result,
+        })
+    }
+}
+
+/// Checks that `picks` numbers may be drawn out of the `1..=pool` range, returning its upper
+/// bound.
+fn check_pool(picks: u32, pool: u32) -> Result<i32> {
+    if pool == 0 || pool > MAX_POOL {
+        return Err(Error::Rest(
+            "The pool of a lottery must be within the [1, 1e9] range.",
+        ));
+    }
+    if picks > pool {
+        return Err(Error::Rest(
+            "A lottery can't draw more numbers than its pool contains.",
+        ));
+    }
+    i32::try_from(pool).map_err(|_| Error::Rest("The pool of a lottery is too large."))
+}
+
+/// Draws `picks` unique numbers out of the `1..=max` range.
+fn draw_unique(random: &Random, picks: u32, max: i32) -> Result<GenerateIntegersResult> {
+    Ok(random
+        .request_integers()
+        .min(1)
+        .max(max)
+        .count(u64::from(picks))
+        .replacement(false)
+        .collect::<Response<GenerateIntegersResult>>()?
+        .result)
+}
+
+/// Returns the numbers of a draw, checking that the service returned `picks` different numbers
+/// of the `1..=pool` range.
+fn drawn_numbers(result: &GenerateIntegersResult, picks: u32, pool: u32) -> Result<Vec<u32>> {
+    let numbers = result
+        .random
+        .data
+        .iter()
+        .map(|&n| {
+            u32::try_from(n)
+                .ok()
+                .filter(|n| (1..=pool).contains(n))
+                .ok_or(Error::Rest(
+                    "The service returned a number out of the pool.",
+                ))
+        })
+        .collect::<Result<Vec<u32>>>()?;
+    let mut drawn = BTreeSet::new();
+    if numbers.len() != picks as usize || !numbers.iter().all(|&n| drawn.insert(n)) {
+        return Err(Error::Rest(
+            "The service did not return as many different numbers as picked.",
+        ));
+    }
+    Ok(numbers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Lottery;
+    use crate::test_server::{client, random_result};
+
+    #[test]
+    fn test_lottery_draws_sorted_numbers_and_bonus_balls() {
+        let random = client(|request| {
+            let params = &request["params"];
+            assert_eq!(params["replacement"], false);
+            let max = params["max"].as_i64().unwrap();
+            let data = match max {
+                50 => vec![42, 7, 19, 3, 50],
+                12 => vec![11, 2],
+                11 => vec![12, 2],
+                max => panic!("Unexpected pool {}", max),
+            };
+            random_result(request, &serde_json::json!(data), 20)
+        });
+
+        let draw = Lottery::new(5, 50).bonus(2, 12).draw(&random).unwrap();
+        assert_eq!(draw.numbers, vec![3, 7, 19, 42, 50]);
+        assert_eq!(draw.bonus, vec![11, 2]);
+        assert_eq!(draw.numbers_result.random.data, vec![42, 7, 19, 3, 50]);
+        assert_eq!(draw.bonus_result.unwrap().bits_used, 20);
+
+        let draw = Lottery::new(5, 50).draw(&random).unwrap();
+        assert!(draw.bonus.is_empty() && draw.bonus_result.is_none());
+        assert!(Lottery::new(51, 50).draw(&random).is_err());
+        assert!(Lottery::new(0, 50).draw(&random).is_err());
+        assert!(Lottery::new(5, 50).bonus(1, 0).draw(&random).is_err());
+        // The bonus ball 12 is out of a pool of 11.
+        assert!(Lottery::new(5, 50).bonus(2, 11).draw(&random).is_err());
+    }
+
+    #[test]
+    fn test_lottery_rejects_repeated_numbers() {
+        let random = client(|request| random_result(request, &serde_json::json!([7, 3, 7]), 20));
+        assert!(Lottery::new(3, 50).draw(&random).is_err());
+        assert!(Lottery::new(2, 50).draw(&random).is_err());
+    }
+}
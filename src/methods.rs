@@ -15,11 +15,11 @@ pub enum Method {
     /// This method generates true random strings.
     #[serde(rename = "generateStrings")]
     GenerateStrings,
-    /// This method generates version 4 true random Universally Unique IDentifiers (UUIDs) in
+    /// This method generates version 4 true random Universally Unique Identifiers (UUIDs) in
     /// accordance with section 4.4 of RFC 4122.
     #[serde(rename = "generateUUIDs")]
     GenerateUUIDs,
-    /// This method generates Binary Large OBjects (BLOBs) containing true random data.
+    /// This method generates Binary Large Objects (BLOBs) containing true random data.
     #[serde(rename = "generateBlobs")]
     GenerateBlobs,
     /// This method returns information related to the the usage of a given API key.
@@ -1,5 +1,6 @@
+use crate::error::{Error, ResponseError, Result};
 use crate::methods::Method;
-use serde::*;
+use serde::{Deserialize, Serialize};
 
 use std::collections::BTreeSet;
 
@@ -8,8 +9,97 @@ use std::collections::BTreeSet;
 pub struct RequestId(pub u64);
 
 /// A random.org api key
-#[derive(Debug, Clone, Serialize)]
-pub struct ApiKey(pub String);
+///
+/// The key never shows up in the `Debug` and `Display` output, and its memory is zeroed when it
+/// is dropped.
+#[derive(Clone, Eq, PartialEq, Serialize)]
+pub struct ApiKey(String);
+
+impl ApiKey {
+    /// Reads the api key from the environment variable, such as `RANDOM_ORG_API_KEY`.
+    ///
+    /// The surrounding whitespace is trimmed and the key must have the UUID-like format of the
+    /// random.org keys.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Rest` if the variable is not set, is not valid unicode or does not hold a
+    /// well-formed key.
+    pub fn from_env(variable: &str) -> Result<ApiKey> {
+        let value = zeroize::Zeroizing::new(std::env::var(variable).map_err(|_| {
+            Error::Rest("The api key environment variable is not set or is not valid unicode.")
+        })?);
+        ApiKey::parse(&value)
+    }
+
+    /// Reads the api key from the file, which holds nothing but the key.
+    ///
+    /// The surrounding whitespace is trimmed and the key must have the UUID-like format of the
+    /// random.org keys.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Io` if the file can't be read, and `Error::Rest` if it does not hold a well-
+    /// formed key.
+    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<ApiKey> {
+        let contents = zeroize::Zeroizing::new(std::fs::read_to_string(path)?);
+        ApiKey::parse(&contents)
+    }
+
+    /// Trims the key and checks its format, `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx` where every
+    /// `x` is a hexadecimal digit.
+    fn parse(key: &str) -> Result<ApiKey> {
+        let key = key.trim();
+        let well_formed = key.len() == 36
+            && key.char_indices().all(|(i, c)| match i {
+                8 | 13 | 18 | 23 => c == '-',
+                _ => c.is_ascii_hexdigit(),
+            });
+        if well_formed {
+            Ok(ApiKey(key.to_owned()))
+        } else {
+            Err(Error::Rest(
+                "The api key is not in the random.org api key format.",
+            ))
+        }
+    }
+
+    /// Returns the key itself, which must be kept secret.
+    #[must_use]
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for ApiKey {
+    fn from(key: String) -> ApiKey {
+        ApiKey(key)
+    }
+}
+
+impl From<&str> for ApiKey {
+    fn from(key: &str) -> ApiKey {
+        ApiKey(key.to_owned())
+    }
+}
+
+impl std::fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ApiKey(<redacted>)")
+    }
+}
+
+impl std::fmt::Display for ApiKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+impl Drop for ApiKey {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.0);
+    }
+}
 
 /// A random.org api key status
 #[derive(Debug, Clone, Eq, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
@@ -27,6 +117,75 @@ pub enum ApiKeyStatus {
 #[derive(Debug, Clone, Serialize)]
 pub struct AllowedCharacters(pub BTreeSet<char>);
 
+/// A decimal number exactly as the service returned it, keeping every digit of it.
+///
+/// With the `decimal` feature it converts to `rust_decimal::Decimal` without losing precision.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ExactDecimal(String);
+
+impl ExactDecimal {
+    /// Returns the number as the service wrote it.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the closest `f64` to the number.
+    #[must_use]
+    pub fn to_f64(&self) -> f64 {
+        self.0.parse().unwrap_or(f64::NAN)
+    }
+
+    /// Returns the number as a `rust_decimal::Decimal`, failing when it does not fit in one.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `rust_decimal` if the number has too many digits or is out of the range
+    /// of a `Decimal`.
+    #[cfg(feature = "decimal")]
+    pub fn to_decimal(&self) -> std::result::Result<rust_decimal::Decimal, rust_decimal::Error> {
+        use std::str::FromStr;
+
+        rust_decimal::Decimal::from_str_exact(&self.0)
+            .or_else(|_| rust_decimal::Decimal::from_scientific(&self.0))
+            .or_else(|_| rust_decimal::Decimal::from_str(&self.0))
+    }
+}
+
+impl std::fmt::Display for ExactDecimal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Writes the number exactly as the service wrote it. Only JSON serializers write it as a
+/// number, as the digits are kept with `serde_json::value::RawValue`.
+impl Serialize for ExactDecimal {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serde_json::value::RawValue::from_string(self.0.clone())
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ExactDecimal {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<ExactDecimal, D::Error> {
+        let raw = Box::<serde_json::value::RawValue>::deserialize(deserializer)?;
+        let number = raw.get().trim();
+        if number.parse::<f64>().is_err() {
+            return Err(serde::de::Error::custom(format!(
+                "expected a decimal number, got {number}"
+            )));
+        }
+        Ok(ExactDecimal(number.to_owned()))
+    }
+}
+
 /// A random.org request holder
 #[derive(Debug, Clone, Serialize)]
 pub struct Request<Params: Serialize> {
@@ -43,7 +202,7 @@ pub struct Request<Params: Serialize> {
 }
 
 /// A random.org response holder
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Response<ResponseResult> {
     /// A json rpc version
     #[serde(rename = "jsonrpc")]
@@ -56,3 +215,75 @@ pub struct Response<ResponseResult> {
     /// will return this unchanged in its response.
     pub id: RequestId,
 }
+
+/// A raw random.org response, which holds either a result or an error.
+#[derive(Debug, Deserialize)]
+pub(crate) struct RawResponse<ResponseResult> {
+    #[serde(rename = "jsonrpc")]
+    json_rpc: String,
+    result: Option<ResponseResult>,
+    error: Option<ResponseError>,
+    id: Option<RequestId>,
+}
+
+impl<ResponseResult> RawResponse<ResponseResult> {
+    /// Turns the raw response into the `Response` or the error it holds.
+    pub(crate) fn into_result(
+        self,
+        status: reqwest::StatusCode,
+    ) -> Result<Response<ResponseResult>> {
+        match (self.result, self.error, self.id) {
+            (_, Some(error), _) => Err(Error::RandomOrg(status, error)),
+            (Some(result), None, Some(id)) => Ok(Response {
+                json_rpc: self.json_rpc,
+                result,
+                id,
+            }),
+            _ => Err(Error::Rest(
+                "The response has neither a result nor an error.",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ApiKey;
+
+    const KEY: &str = "6b1e65b9-4186-45c2-8981-b77a9842c4f0";
+
+    #[test]
+    fn test_api_key_is_redacted() {
+        let key = ApiKey::from(KEY);
+
+        assert_eq!(format!("{key:?}"), "ApiKey(<redacted>)");
+        assert_eq!(key.to_string(), "<redacted>");
+        assert!(!format!("{:?}", crate::Random::new(KEY)).contains(KEY));
+        assert_eq!(key.expose_secret(), KEY);
+    }
+
+    #[test]
+    fn test_api_key_from_env_and_file() {
+        std::env::set_var("RANDOMORG_TEST_API_KEY", format!("  {KEY}\n"));
+        assert_eq!(
+            ApiKey::from_env("RANDOMORG_TEST_API_KEY")
+                .unwrap()
+                .expose_secret(),
+            KEY
+        );
+        std::env::set_var("RANDOMORG_TEST_API_KEY", "API KEY HERE");
+        assert!(ApiKey::from_env("RANDOMORG_TEST_API_KEY").is_err());
+        assert!(ApiKey::from_env("RANDOMORG_TEST_MISSING_API_KEY").is_err());
+
+        let path = std::env::temp_dir().join(format!("randomorg-key-{}", std::process::id()));
+        std::fs::write(&path, format!("{}\n", KEY.to_uppercase())).unwrap();
+        assert_eq!(
+            ApiKey::from_file(&path).unwrap().expose_secret(),
+            KEY.to_uppercase()
+        );
+        std::fs::write(&path, "6b1e65b9-4186-45c2-8981-b77a9842c4f").unwrap();
+        assert!(ApiKey::from_file(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+        assert!(ApiKey::from_file(&path).is_err());
+    }
+}
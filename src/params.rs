@@ -1,4 +1,5 @@
 use crate::model::ApiKey;
+use crate::{Error, Result};
 
 /// A random.org api key params
 #[derive(Debug, Clone, serde::Serialize)]
@@ -61,10 +62,10 @@ pub struct GenerateGaussiansParams {
     #[serde(rename = "n")]
     pub limit: u16,
     /// The distribution's mean. Must be within the [-1e6,1e6] range.
-    pub mean: i32,
+    pub mean: f64,
     /// The distribution's standard deviation. Must be within the [-1e6,1e6] range.
     #[serde(rename = "standardDeviation")]
-    pub standard_deviation: i32,
+    pub standard_deviation: f64,
     /// The number of significant digits to use. Must be within the [2,20] range.
     #[serde(rename = "significantDigits")]
     pub significant_digits: u8,
@@ -111,3 +112,174 @@ pub struct GenerateBlobsParams {
     /// divisible by 8.
     pub size: u32,
 }
+
+/// A number of true random bits the service is expected to use to serve a request.
+pub(crate) trait EstimatedBits {
+    /// Returns the expected `bitsUsed` of the request.
+    fn estimated_bits(&self) -> u64;
+}
+
+macro_rules! estimated_bits {
+    ($($params:ty),*) => {
+        $(impl EstimatedBits for $params {
+            fn estimated_bits(&self) -> u64 {
+                <$params>::estimated_bits(self)
+            }
+        })*
+    };
+}
+
+estimated_bits!(
+    ApiKeyParams,
+    GenerateIntegersParams,
+    GenerateDecimalFractionsParams,
+    GenerateGaussiansParams,
+    GenerateStringsParams,
+    GenerateUUIDsParams,
+    GenerateBlobsParams
+);
+
+/// The bound of the mean and the standard deviation of gaussians accepted by the service.
+const MAX_GAUSSIANS_PARAMETER: f64 = 1e6;
+
+/// Checks that the mean and the standard deviation of gaussians are within the [-1e6, 1e6]
+/// range accepted by the service.
+pub(crate) fn check_gaussians(mean: f64, standard_deviation: f64) -> Result<()> {
+    let range = -MAX_GAUSSIANS_PARAMETER..=MAX_GAUSSIANS_PARAMETER;
+    if !range.contains(&mean) {
+        return Err(Error::Rest(
+            "The mean of gaussians must be within the [-1e6, 1e6] range.",
+        ));
+    }
+    if !range.contains(&standard_deviation) {
+        return Err(Error::Rest(
+            "The standard deviation of gaussians must be within the [-1e6, 1e6] range.",
+        ));
+    }
+    Ok(())
+}
+
+/// Rounds the total number of bits of `count` values carrying `bits` bits each, the same way
+/// the service reports its `bitsUsed`.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+fn total_bits(count: u64, bits: f64) -> u64 {
+    (count as f64 * bits).round() as u64
+}
+
+/// Returns the base 2 logarithm of `n!`, summed exactly for small numbers and approximated
+/// with the Stirling series for the others.
+#[allow(clippy::cast_precision_loss)]
+fn log2_factorial(n: u64) -> f64 {
+    if n < 1024 {
+        return (2..=n).map(|i| (i as f64).log2()).sum();
+    }
+    let n = n as f64;
+    (n * n.ln() - n + 0.5 * (2.0 * std::f64::consts::PI * n).ln() + 1.0 / (12.0 * n))
+        / std::f64::consts::LN_2
+}
+
+/// Estimates the bits used by a `generateIntegers` request.
+#[allow(clippy::cast_precision_loss, clippy::cast_sign_loss)]
+pub(crate) fn integers_bits(min: i32, max: i32, count: u64, replacement: bool) -> u64 {
+    let range = (i64::from(max) - i64::from(min) + 1).max(1) as u64;
+    if replacement {
+        total_bits(count, (range as f64).log2())
+    } else {
+        // Every unique value picked leaves one value less to pick from: the picks are among
+        // the range! / (range - count)! ordered selections.
+        let picked = count.min(range);
+        total_bits(1, log2_factorial(range) - log2_factorial(range - picked))
+    }
+}
+
+/// Estimates the bits used by a `generateDecimalFractions` request.
+pub(crate) fn decimal_fractions_bits(count: u64, decimal_places: u8) -> u64 {
+    total_bits(count, f64::from(decimal_places) * 10f64.log2())
+}
+
+/// Estimates the bits used by a `generateGaussians` request.
+pub(crate) fn gaussians_bits(count: u64, significant_digits: u8) -> u64 {
+    total_bits(count, f64::from(significant_digits) * 10f64.log2())
+}
+
+/// Estimates the bits used by a `generateStrings` request.
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn strings_bits(count: u64, length: u8, characters: usize) -> u64 {
+    total_bits(count, f64::from(length) * (characters.max(1) as f64).log2())
+}
+
+/// Estimates the bits used by a `generateUUIDs` request.
+pub(crate) fn uuids_bits(count: u64) -> u64 {
+    // Version 4 UUIDs carry 122 random bits, the rest are the fixed version and variant bits.
+    count * 122
+}
+
+/// Estimates the bits used by a `generateBlobs` request.
+pub(crate) fn blobs_bits(count: u64, size: u32) -> u64 {
+    count * u64::from(size)
+}
+
+impl ApiKeyParams {
+    /// Returns the number of true random bits the request is expected to use, which is zero
+    /// as the `getUsage` method does not use any.
+    #[must_use]
+    pub fn estimated_bits(&self) -> u64 {
+        0
+    }
+}
+
+impl GenerateIntegersParams {
+    /// Returns the expected `bitsUsed` of the request.
+    #[must_use]
+    pub fn estimated_bits(&self) -> u64 {
+        integers_bits(self.min, self.max, u64::from(self.limit), self.replacement)
+    }
+}
+
+impl GenerateDecimalFractionsParams {
+    /// Returns the expected `bitsUsed` of the request.
+    #[must_use]
+    pub fn estimated_bits(&self) -> u64 {
+        decimal_fractions_bits(u64::from(self.limit), self.decimal_places)
+    }
+}
+
+impl GenerateGaussiansParams {
+    /// Returns the expected `bitsUsed` of the request.
+    #[must_use]
+    pub fn estimated_bits(&self) -> u64 {
+        gaussians_bits(u64::from(self.limit), self.significant_digits)
+    }
+}
+
+impl GenerateStringsParams {
+    /// Returns the expected `bitsUsed` of the request.
+    #[must_use]
+    pub fn estimated_bits(&self) -> u64 {
+        strings_bits(
+            u64::from(self.limit),
+            self.length,
+            self.characters.chars().count(),
+        )
+    }
+}
+
+impl GenerateUUIDsParams {
+    /// Returns the expected `bitsUsed` of the request.
+    #[must_use]
+    pub fn estimated_bits(&self) -> u64 {
+        uuids_bits(u64::from(self.limit))
+    }
+}
+
+impl GenerateBlobsParams {
+    /// Returns the expected `bitsUsed` of the request.
+    #[must_use]
+    pub fn estimated_bits(&self) -> u64 {
+        blobs_bits(u64::from(self.limit), self.size)
+    }
+}
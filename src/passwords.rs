@@ -0,0 +1,391 @@
+//! Passwords and passphrases made of values generated by random.org.
+//!
+//! Every character of a password and every word of a passphrase is picked with an integer
+//! drawn uniformly by `generate_integers`, so neither is biased, and they are not bound by the
+//! length and alphabet limits of `generate_strings`. The generated secrets report their
+//! entropy in bits.
+//!
+//! # Usage
+//!
+//! ```rust,no_run
+//! use randomorg::passwords::{parse_wordlist, passphrase, CharacterClass, PasswordPolicy};
+//! use randomorg::Random;
+//!
+//! let random = Random::new("API KEY HERE");
+//! let password = PasswordPolicy::new(32)
+//!     .classes(&[CharacterClass::Lowercase, CharacterClass::Uppercase, CharacterClass::Digits])
+//!     .exclude_ambiguous(true)
+//!     .generate(&random)
+//!     .unwrap();
+//! println!("{} bits of entropy", password.entropy_bits());
+//!
+//! // The EFF wordlists are available at https://www.eff.org/dice.
+//! let wordlist = std::fs::read_to_string("eff_large_wordlist.txt").unwrap();
+//! let words = parse_wordlist(&wordlist);
+//! let passphrase = passphrase(&random, &words, 6, "-").unwrap();
+//! println!("{} bits of entropy", passphrase.entropy_bits());
+//! ```
+
+use crate::{Error, Random, Result};
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+
+/// The characters which are easily mistaken for one another.
+const AMBIGUOUS: &str = "0Oo1lI|`'\"";
+/// The most attempts at generating a password meeting a policy.
+const MAX_ATTEMPTS: u32 = 100;
+
+/// A class of the characters of a password.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum CharacterClass {
+    /// The `a-z` letters.
+    Lowercase,
+    /// The `A-Z` letters.
+    Uppercase,
+    /// The `0-9` digits.
+    Digits,
+    /// The ASCII punctuation characters.
+    Symbols,
+}
+
+impl CharacterClass {
+    /// Returns the characters of the class.
+    #[must_use]
+    pub fn characters(self) -> &'static str {
+        match self {
+            CharacterClass::Lowercase => "abcdefghijklmnopqrstuvwxyz",
+            CharacterClass::Uppercase => "ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+            CharacterClass::Digits => "0123456789",
+            CharacterClass::Symbols => "!\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~",
+        }
+    }
+}
+
+/// A generated password or passphrase.
+///
+/// The secret never shows up in the `Debug` output, and its memory is zeroed when it is
+/// dropped.
+#[derive(Clone)]
+pub struct Password {
+    secret: String,
+    entropy_bits: f64,
+}
+
+impl Password {
+    /// Returns the password itself, which must be kept secret.
+    #[must_use]
+    pub fn expose_secret(&self) -> &str {
+        &self.secret
+    }
+
+    /// Returns the entropy of the password in bits: the base 2 logarithm of the number of
+    /// passwords it was picked from.
+    #[must_use]
+    pub fn entropy_bits(&self) -> f64 {
+        self.entropy_bits
+    }
+}
+
+impl std::fmt::Debug for Password {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Password")
+            .field("secret", &"<redacted>")
+            .field("entropy_bits", &self.entropy_bits)
+            .finish()
+    }
+}
+
+impl Drop for Password {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.secret);
+    }
+}
+
+/// Draws `count` indices of the `0..len` range, to pick the characters or the words of a
+/// secret with.
+fn draw_indices(random: &Random, len: usize, count: usize) -> Result<Vec<usize>> {
+    let max = i32::try_from(len - 1).map_err(|_| Error::Rest("The alphabet is too large."))?;
+    let mut drawn = random
+        .request_integers()
+        .min(0)
+        .max(max)
+        .count(count as u64)
+        .replacement(true)
+        .collect::<Vec<i32>>()?;
+    let indices = drawn
+        .iter()
+        .map(|&i| usize::try_from(i).ok().filter(|&i| i < len))
+        .collect::<Option<Vec<usize>>>()
+        .ok_or(Error::Rest(
+            "The service returned an integer out of the range.",
+        ));
+    zeroize::Zeroize::zeroize(&mut drawn);
+    indices
+}
+
+/// The rules a generated password follows.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PasswordPolicy {
+    length: usize,
+    classes: BTreeSet<CharacterClass>,
+    excluded: BTreeSet<char>,
+    exclude_ambiguous: bool,
+}
+
+impl PasswordPolicy {
+    /// Creates a policy of passwords of `length` characters, using every character class.
+    #[must_use]
+    pub fn new(length: usize) -> PasswordPolicy {
+        PasswordPolicy {
+            length,
+            classes: [
+                CharacterClass::Lowercase,
+                CharacterClass::Uppercase,
+                CharacterClass::Digits,
+                CharacterClass::Symbols,
+            ]
+            .iter()
+            .copied()
+            .collect(),
+            excluded: BTreeSet::new(),
+            exclude_ambiguous: false,
+        }
+    }
+
+    /// A builder method. Sets the character classes of the passwords: every password contains
+    /// at least a character of each one.
+    #[must_use]
+    pub fn classes(mut self, classes: &[CharacterClass]) -> Self {
+        self.classes = classes.iter().copied().collect();
+        self
+    }
+
+    /// A builder method. Excludes the characters which are easily mistaken for one another,
+    /// like `0` and `O` or `1`, `l` and `I`.
+    #[must_use]
+    pub fn exclude_ambiguous(mut self, exclude: bool) -> Self {
+        self.exclude_ambiguous = exclude;
+        self
+    }
+
+    /// A builder method. Excludes the `characters` from the passwords.
+    #[must_use]
+    pub fn exclude(mut self, characters: &str) -> Self {
+        self.excluded.extend(characters.chars());
+        self
+    }
+
+    /// Returns `true` if the character may not be part of the passwords.
+    fn is_excluded(&self, c: char) -> bool {
+        self.excluded.contains(&c) || (self.exclude_ambiguous && AMBIGUOUS.contains(c))
+    }
+
+    /// Returns the characters of every class, without the excluded ones.
+    fn class_alphabets(&self) -> Result<Vec<Vec<char>>> {
+        if self.classes.is_empty() {
+            return Err(Error::Rest("A password policy needs a character class."));
+        }
+        if self.length < self.classes.len() {
+            return Err(Error::Rest(
+                "A password must be long enough to contain every character class.",
+            ));
+        }
+        self.classes
+            .iter()
+            .map(|class| {
+                let alphabet = class
+                    .characters()
+                    .chars()
+                    .filter(|&c| !self.is_excluded(c))
+                    .collect::<Vec<char>>();
+                if alphabet.is_empty() {
+                    Err(Error::Rest("Every character of a class is excluded."))
+                } else {
+                    Ok(alphabet)
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the entropy of the passwords of the policy: the passwords made of the alphabet
+    /// which contain a character of every class are counted by inclusion-exclusion.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Rest` if the policy has no character class, a class whose every character is
+    /// excluded, or a length too short to contain every class.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn entropy_bits(&self) -> Result<f64> {
+        let sizes = self
+            .class_alphabets()?
+            .iter()
+            .map(|alphabet| alphabet.len() as f64)
+            .collect::<Vec<f64>>();
+        let total = sizes.iter().sum::<f64>();
+        let length =
+            i32::try_from(self.length).map_err(|_| Error::Rest("The password is too long."))?;
+        let valid = (0..1u32 << sizes.len())
+            .map(|subset| {
+                let missing = (0..sizes.len())
+                    .filter(|i| subset & (1 << i) != 0)
+                    .map(|i| sizes[i])
+                    .sum::<f64>();
+                let sign = if subset.count_ones() % 2 == 0 {
+                    1.0
+                } else {
+                    -1.0
+                };
+                sign * ((total - missing) / total).powi(length)
+            })
+            .sum::<f64>();
+        Ok(self.length as f64 * total.log2() + valid.log2())
+    }
+
+    /// Generates a password following the policy (performs the requests).
+    ///
+    /// The passwords missing a character class are drawn again, so that every password
+    /// following the policy is equally likely.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Rest` if the policy can't be followed or no password containing every class
+    /// was drawn in 100 attempts, and the error of the service if a call fails.
+    pub fn generate(&self, random: &Random) -> Result<Password> {
+        let alphabets = self.class_alphabets()?;
+        let entropy_bits = self.entropy_bits()?;
+        let alphabet = alphabets.concat();
+        for _ in 0..MAX_ATTEMPTS {
+            let secret = draw_indices(random, alphabet.len(), self.length)?
+                .into_iter()
+                .map(|i| alphabet[i])
+                .collect::<String>();
+            let password = Password {
+                secret,
+                entropy_bits,
+            };
+            let complete = alphabets
+                .iter()
+                .all(|class| password.secret.chars().any(|c| class.contains(&c)));
+            if complete {
+                return Ok(password);
+            }
+        }
+        Err(Error::Rest(
+            "Could not generate a password containing every character class.",
+        ))
+    }
+}
+
+/// Reads a wordlist of a word per line, like the EFF wordlists: a word may be preceded by its
+/// dice code, which is skipped.
+#[must_use]
+pub fn parse_wordlist(wordlist: &str) -> Vec<&str> {
+    wordlist
+        .lines()
+        .filter_map(|line| line.split_whitespace().last())
+        .collect()
+}
+
+/// Generates a diceware-style passphrase of `words` words of the `wordlist`, joined with the
+/// `separator` (performs the request).
+///
+/// Every word is picked with an integer drawn over the indices of the wordlist, which is like
+/// rolling the dice of the word's code.
+///
+/// # Constraints
+/// * `words` must be positive
+/// * the words of the wordlist must be unique
+/// * the wordlist must have within [1; 1e9 + 1] words
+///
+/// # Errors
+///
+/// Returns `Error::Rest` if a constraint is broken, and the error of the service if the call fails.
+#[allow(clippy::cast_precision_loss)]
+pub fn passphrase(
+    random: &Random,
+    wordlist: &[&str],
+    words: usize,
+    separator: &str,
+) -> Result<Password> {
+    if wordlist.is_empty() || words == 0 {
+        return Err(Error::Rest(
+            "A passphrase needs at least a word out of a non-empty wordlist.",
+        ));
+    }
+    if wordlist.iter().collect::<BTreeSet<_>>().len() != wordlist.len() {
+        return Err(Error::Rest("The words of a wordlist must be unique."));
+    }
+    let secret = draw_indices(random, wordlist.len(), words)?
+        .into_iter()
+        .map(|i| wordlist[i])
+        .collect::<Vec<&str>>()
+        .join(separator);
+    Ok(Password {
+        secret,
+        entropy_bits: words as f64 * (wordlist.len() as f64).log2(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_wordlist, passphrase, CharacterClass, PasswordPolicy};
+    use crate::test_server::{client, random_result};
+    use crate::Random;
+
+    /// Serves the integers of the `calls`, one call after another.
+    fn calls_server(calls: Vec<Vec<i32>>) -> Random {
+        let served = std::sync::atomic::AtomicUsize::new(0);
+        client(move |request| {
+            let call = served.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            random_result(request, &serde_json::json!(calls[call]), 20)
+        })
+    }
+
+    #[test]
+    fn test_password_contains_every_class() {
+        // Without the ambiguous characters, the 24 letters come before the 8 digits.
+        let random = calls_server(vec![vec![0, 1, 2, 3], vec![0, 31, 24, 5]]);
+        let policy = PasswordPolicy::new(4)
+            .classes(&[CharacterClass::Lowercase, CharacterClass::Digits])
+            .exclude_ambiguous(true);
+        let password = policy.generate(&random).unwrap();
+        assert_eq!(password.expose_secret(), "a92f");
+        let expected = 20.0 + (1.0 - 0.25f64.powi(4) - 0.75f64.powi(4)).log2();
+        assert!((password.entropy_bits() - expected).abs() < 1e-9);
+        assert!(!format!("{password:?}").contains("a92f"));
+
+        assert!(PasswordPolicy::new(1).generate(&random).is_err());
+        // Allowing the ambiguous characters again keeps the ones excluded explicitly.
+        assert!(PasswordPolicy::new(8)
+            .classes(&[CharacterClass::Digits])
+            .exclude("023456789")
+            .exclude_ambiguous(true)
+            .exclude_ambiguous(false)
+            .entropy_bits()
+            .is_ok());
+        assert!(PasswordPolicy::new(8)
+            .classes(&[CharacterClass::Digits])
+            .exclude("123456789")
+            .exclude("0")
+            .exclude_ambiguous(false)
+            .entropy_bits()
+            .is_err());
+        assert!(PasswordPolicy::new(8)
+            .classes(&[CharacterClass::Digits])
+            .exclude("0123456789")
+            .generate(&random)
+            .is_err());
+    }
+
+    #[test]
+    fn test_passphrase_picks_words() {
+        let random = calls_server(vec![vec![2, 0]]);
+        let wordlist = parse_wordlist("11111\tabacus\n11112\tabdomen\n\n11113 abdominal\n");
+        assert_eq!(wordlist, vec!["abacus", "abdomen", "abdominal"]);
+        let phrase = passphrase(&random, &wordlist, 2, "-").unwrap();
+        assert_eq!(phrase.expose_secret(), "abdominal-abacus");
+        assert!((phrase.entropy_bits() - 2.0 * 3f64.log2()).abs() < 1e-9);
+        assert!(passphrase(&random, &["a", "a"], 2, " ").is_err());
+        assert!(passphrase(&random, &wordlist, 0, " ").is_err());
+    }
+}
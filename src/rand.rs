@@ -1,4 +1,4 @@
-//! A rand_core::Rng implementation.
+//! A `rand_core::Rng` implementation.
 //! Available with the `rand` feature.
 //!
 //! # Usage
@@ -13,6 +13,16 @@
 //! let random_u64 = random.next_u64();
 //! ```
 
+#[cfg(feature = "distributions")]
+pub mod distributions;
+
+use crate::reader::decode_blob;
+use crate::request_builders::MAX_BLOBS_SIZE;
+use rand_chacha::ChaCha20Rng;
+use rand_core::{OsRng, RngCore};
+use std::convert::TryFrom;
+use std::time::{Duration, Instant};
+
 impl From<crate::Error> for rand_core::Error {
     fn from(e: crate::Error) -> rand_core::Error {
         rand_core::Error::new(e)
@@ -20,30 +30,40 @@ impl From<crate::Error> for rand_core::Error {
 }
 
 impl crate::Random {
-    fn rand_next_i32(&self) -> Result<i32, crate::Error> {
-        Ok(self
-            .generate_integers(std::i32::MIN, std::i32::MAX, 1, true)?
-            .result
-            .random
-            .data[0])
+    /// Fills `dest` with the bytes of a single blob, or of several ones when `dest` is larger
+    /// than the biggest blob the service generates in a call.
+    fn fill_blob_bytes(&self, dest: &mut [u8]) -> Result<(), crate::Error> {
+        let max_bytes = usize::try_from(MAX_BLOBS_SIZE / 8).unwrap_or(usize::MAX);
+        for chunk in dest.chunks_mut(max_bytes) {
+            let size = u32::try_from(chunk.len() * 8).map_err(|_| {
+                crate::Error::Rest("The blob is too large for the random.org service.")
+            })?;
+            let blobs = self.generate_blobs(1, size)?.result.random.data;
+            let bytes = decode_blob(blobs.first().map_or("", String::as_str))?;
+            if bytes.len() != chunk.len() {
+                return Err(crate::Error::Rest(
+                    "The service returned a blob of a wrong size.",
+                ));
+            }
+            chunk.copy_from_slice(&bytes);
+        }
+        Ok(())
     }
 }
 
 // Optimize the implementation:
-// 1. on `next_u**` calls collect as much integers as possible and store them in some
-// structure. Return new integers from the vector of this structure and remove it from it.
-// Once the vector is empty, request another portion.
+// 1. on `next_u**` calls request more bytes than needed and store them in some structure.
+// Return new integers from the bytes of this structure and remove them from it.
+// Once the bytes are used up, request another portion.
 // 2. Don't panic after a single attempt of `try_fill_bytes` in the `fill_bytes`.
 // Try like 3 times and only then panic. The implementation must not be fallible if possible.
 impl rand_core::RngCore for crate::Random {
     fn next_u32(&mut self) -> u32 {
-        self.rand_next_i32()
-            .expect("Could not request an integer (u32).") as u32
+        rand_core::impls::next_u32_via_fill(self)
     }
 
     fn next_u64(&mut self) -> u64 {
-        self.rand_next_i32()
-            .expect("Could not request an integer (u64).") as u64
+        rand_core::impls::next_u64_via_fill(self)
     }
 
     fn fill_bytes(&mut self, dest: &mut [u8]) {
@@ -53,83 +73,664 @@ impl rand_core::RngCore for crate::Random {
     }
 
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
-        let limit = dest.len();
-        if limit > std::u16::MAX as usize {
-            return Err(crate::Error::Io(std::io::Error::new(
-                std::io::ErrorKind::InvalidInput,
-                "Maximum length is 100, mandated by the random.org service.",
-            ))
-            .into());
-        }
-        let res = self.generate_blobs(limit as u16, 8)?.result.random.data;
-        for i in 0..limit {
-            dest[i] = res[i].parse().map_err(crate::Error::from)?;
-        }
-        Ok(())
+        Ok(self.fill_blob_bytes(dest)?)
     }
 }
 
 impl rand_core::CryptoRng for crate::Random {}
 
+/// The default number of consecutive failures which opens the circuit of `FallibleRandom`.
+const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+/// The default time the circuit of `FallibleRandom` stays open before a probe.
+const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+
+type FallbackCallback = Box<dyn FnMut(Option<&crate::Error>) + Send>;
+
+/// The state of the circuit breaker of `FallibleRandom`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CircuitState {
+    /// The service is called for every value.
+    Closed,
+    /// The service failed too many times in a row, so the values come from the fallback
+    /// without calling it until the cooldown is over.
+    Open,
+    /// The cooldown is over: the next value probes the service, closing the circuit on
+    /// success and opening it again on failure.
+    HalfOpen,
+}
+
+/// A circuit breaker, which stops calling the service after a number of consecutive failures
+/// until a cooldown is over.
+struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    fn new() -> CircuitBreaker {
+        CircuitBreaker {
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            cooldown: DEFAULT_COOLDOWN,
+            consecutive_failures: 0,
+            opened_at: None,
+        }
+    }
+
+    fn state(&self) -> CircuitState {
+        match self.opened_at {
+            None => CircuitState::Closed,
+            Some(opened_at) if opened_at.elapsed() < self.cooldown => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    fn succeeded(&mut self) {
+        self.consecutive_failures = 0;
+        self.opened_at = None;
+    }
+
+    /// Records a failed call made while the circuit was in the `state`.
+    fn failed(&mut self, state: CircuitState) {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        if state == CircuitState::HalfOpen || self.consecutive_failures >= self.failure_threshold {
+            self.opened_at = Some(Instant::now());
+        }
+    }
+}
+
+/// The health of a `FallibleRandom` generator.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Health {
+    /// The number of values (integers or filled buffers) served by random.org.
+    pub remote_values: u64,
+    /// The number of values (integers or filled buffers) served by the fallback.
+    pub fallback_values: u64,
+    /// The number of failed calls to random.org.
+    pub failures: u64,
+    /// The state of the circuit breaker.
+    pub circuit: CircuitState,
+}
+
 /// A fallible implementation, which uses the supplied implementation as a fallback,
 /// when the random.org service is unavailable.
 ///
+/// After a number of consecutive failures the service is considered down: the values come
+/// from the fallback without calling it, until a cooldown is over and a single call probes
+/// whether the service is back.
+///
 /// # Usage
 ///
-/// ```rust,ignore
-/// use rand_os::OsRng;
-/// use rand_core::RngCore;
+/// ```rust,no_run
+/// use rand_core::{OsRng, RngCore};
 /// use randomorg::{rand::FallibleRandom, Random};
+/// use std::time::Duration;
 ///
 /// let random = Random::new("API KEY HERE");
-/// let fallible = FallibleRandom { fallback: OsRng, random };
+/// let mut fallible = FallibleRandom::new(random, OsRng)
+///     .failure_threshold(5)
+///     .cooldown(Duration::from_secs(60))
+///     .on_fallback(|e| eprintln!("Falling back to OsRng: {:?}", e));
 /// let mut key = [0u8; 16];
 /// fallible.fill_bytes(&mut key);
 /// let random_u64 = fallible.next_u64();
+/// println!("Health: {:?}", fallible.health());
 /// ```
 pub struct FallibleRandom<T: rand_core::RngCore> {
     /// Fallback generator, used when `Random` does not work.
     pub fallback: T,
     /// A random.org generator.
     pub random: crate::Random,
+    breaker: CircuitBreaker,
+    on_fallback: Option<FallbackCallback>,
+    remote_values: u64,
+    fallback_values: u64,
+    failures: u64,
+}
+
+impl<T: rand_core::RngCore> FallibleRandom<T> {
+    /// Creates a generator which falls back to `fallback` when `random` does not work.
+    pub fn new(random: crate::Random, fallback: T) -> FallibleRandom<T> {
+        FallibleRandom {
+            fallback,
+            random,
+            breaker: CircuitBreaker::new(),
+            on_fallback: None,
+            remote_values: 0,
+            fallback_values: 0,
+            failures: 0,
+        }
+    }
+
+    /// Sets the number of consecutive failures which opens the circuit. Defaults to 3.
+    #[must_use]
+    pub fn failure_threshold(mut self, failures: u32) -> Self {
+        self.breaker.failure_threshold = failures.max(1);
+        self
+    }
+
+    /// Sets the time the circuit stays open before the service is probed again. Defaults to
+    /// 30 seconds.
+    #[must_use]
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.breaker.cooldown = cooldown;
+        self
+    }
+
+    /// Sets a callback called whenever a value comes from the fallback, with the error of the
+    /// service, or `None` when the service was not called because the circuit is open.
+    #[must_use]
+    pub fn on_fallback<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(Option<&crate::Error>) + Send + 'static,
+    {
+        self.on_fallback = Some(Box::new(callback));
+        self
+    }
+
+    /// Returns how many values were served by each source and the state of the circuit.
+    pub fn health(&self) -> Health {
+        Health {
+            remote_values: self.remote_values,
+            fallback_values: self.fallback_values,
+            failures: self.failures,
+            circuit: self.breaker.state(),
+        }
+    }
+
+    /// Obtains a value from the service unless the circuit is open, and records the outcome.
+    /// Returns `None` when the value must come from the fallback.
+    fn remote<R, F>(&mut self, request: F) -> Option<R>
+    where
+        F: FnOnce(&crate::Random) -> crate::Result<R>,
+    {
+        let circuit = self.breaker.state();
+        let error = if circuit == CircuitState::Open {
+            None
+        } else {
+            match request(&self.random) {
+                Ok(value) => {
+                    self.breaker.succeeded();
+                    self.remote_values += 1;
+                    return Some(value);
+                }
+                Err(e) => {
+                    log::warn!("Could not obtain a value with FallibleRandom(randomorg): {e}");
+                    self.failures += 1;
+                    self.breaker.failed(circuit);
+                    Some(e)
+                }
+            }
+        };
+        self.fallback_values += 1;
+        if let Some(callback) = self.on_fallback.as_mut() {
+            callback(error.as_ref());
+        }
+        None
+    }
 }
 
 impl<T: rand_core::RngCore> rand_core::RngCore for FallibleRandom<T> {
     fn next_u32(&mut self) -> u32 {
-        self.random
-            .rand_next_i32()
-            .map(|n| n as u32)
-            .unwrap_or_else(|_| self.fallback.next_u32())
+        rand_core::impls::next_u32_via_fill(self)
     }
 
     fn next_u64(&mut self) -> u64 {
-        self.random
-            .rand_next_i32()
-            .map(|n| n as u64)
-            .unwrap_or_else(|_| self.fallback.next_u64())
+        rand_core::impls::next_u64_via_fill(self)
     }
 
     fn fill_bytes(&mut self, dest: &mut [u8]) {
-        if let Err(e) = self.try_fill_bytes(dest) {
-            log::warn!(
-                "Could not fill the bytes with FallibleRandom(randomorg): {}",
-                e
-            );
+        if self.remote(|r| r.fill_blob_bytes(dest)).is_none() {
             self.fallback.fill_bytes(dest);
         }
     }
 
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
-        if let Err(e) = self.random.try_fill_bytes(dest) {
-            log::warn!(
-                "Could not fill the bytes with FallibleRandom(randomorg): {}",
-                e
-            );
-            self.fallback.try_fill_bytes(dest)
-        } else {
-            Ok(())
+        match self.remote(|r| r.fill_blob_bytes(dest)) {
+            Some(()) => Ok(()),
+            None => self.fallback.try_fill_bytes(dest),
         }
     }
 }
 
 impl<T: rand_core::RngCore> rand_core::CryptoRng for FallibleRandom<T> {}
+
+/// A local generator seeded with 256 bits of random.org entropy, which produces the values
+/// without a network call and reseeds itself after a number of bytes or some time.
+///
+/// # Usage
+///
+/// ```rust,no_run
+/// use rand_core::RngCore;
+/// use randomorg::{rand::SeededRandom, Random};
+/// use std::time::Duration;
+///
+/// let mut rng = SeededRandom::new(Random::new("API KEY HERE"))
+///     .mix_os_rng(true)
+///     .reseed_after_bytes(1 << 20)
+///     .reseed_after(Duration::from_secs(600));
+/// let mut key = [0u8; 1024];
+/// rng.fill_bytes(&mut key);
+/// let random_u64 = rng.next_u64();
+/// ```
+pub struct SeededRandom<R = ChaCha20Rng> {
+    random: crate::Random,
+    rng: Option<R>,
+    mix_os_rng: bool,
+    reseed_after_bytes: Option<u64>,
+    reseed_after: Option<Duration>,
+    bytes_since_seed: u64,
+    seeded_at: Instant,
+}
+
+impl SeededRandom<ChaCha20Rng> {
+    /// Creates a `ChaCha20` generator seeded by the client. The seed is requested on the first
+    /// use of the generator.
+    #[must_use]
+    pub fn new(random: crate::Random) -> SeededRandom<ChaCha20Rng> {
+        SeededRandom::with_rng(random)
+    }
+}
+
+impl<R> SeededRandom<R>
+where
+    R: rand_core::SeedableRng<Seed = [u8; 32]> + rand_core::RngCore,
+{
+    /// Creates a generator of any seedable type seeded by the client. The seed is requested
+    /// on the first use of the generator.
+    #[must_use]
+    pub fn with_rng(random: crate::Random) -> SeededRandom<R> {
+        SeededRandom {
+            random,
+            rng: None,
+            mix_os_rng: false,
+            reseed_after_bytes: None,
+            reseed_after: None,
+            bytes_since_seed: 0,
+            seeded_at: Instant::now(),
+        }
+    }
+
+    /// Mixes the bytes of the operating system generator (`OsRng`) into every seed, so the
+    /// seed stays unpredictable even to the random.org service. Disabled by default.
+    #[must_use]
+    pub fn mix_os_rng(mut self, mix_os_rng: bool) -> Self {
+        self.mix_os_rng = mix_os_rng;
+        self
+    }
+
+    /// Requests a new seed once the generator has produced `bytes` bytes.
+    #[must_use]
+    pub fn reseed_after_bytes(mut self, bytes: u64) -> Self {
+        self.reseed_after_bytes = Some(bytes);
+        self
+    }
+
+    /// Requests a new seed once the current one is older than `period`.
+    #[must_use]
+    pub fn reseed_after(mut self, period: Duration) -> Self {
+        self.reseed_after = Some(period);
+        self
+    }
+
+    /// Requests a new seed from the service right away.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the service or of the operating system generator if the seed can't be
+    /// drawn, keeping the current seed.
+    pub fn reseed(&mut self) -> crate::Result<()> {
+        let mut seed = [0u8; 32];
+        self.random.fill_blob_bytes(&mut seed)?;
+        if self.mix_os_rng {
+            let mut local = [0u8; 32];
+            OsRng
+                .try_fill_bytes(&mut local)
+                .map_err(|e| crate::Error::Io(std::io::Error::other(e)))?;
+            seed.iter_mut().zip(&local).for_each(|(s, l)| *s ^= l);
+        }
+        self.rng = Some(R::from_seed(seed));
+        self.bytes_since_seed = 0;
+        self.seeded_at = Instant::now();
+        Ok(())
+    }
+
+    fn needs_seed(&self) -> bool {
+        self.rng.is_none()
+            || self
+                .reseed_after_bytes
+                .is_some_and(|bytes| self.bytes_since_seed >= bytes)
+            || self
+                .reseed_after
+                .is_some_and(|period| self.seeded_at.elapsed() >= period)
+    }
+}
+
+impl<R> rand_core::RngCore for SeededRandom<R>
+where
+    R: rand_core::SeedableRng<Seed = [u8; 32]> + rand_core::RngCore,
+{
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        if let Err(e) = self.try_fill_bytes(dest) {
+            panic!("Error: {}", e);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        if self.needs_seed() {
+            self.reseed()?;
+        }
+        if let Some(rng) = self.rng.as_mut() {
+            rng.try_fill_bytes(dest)?;
+        }
+        let produced = u64::try_from(dest.len()).unwrap_or(u64::MAX);
+        self.bytes_since_seed = self.bytes_since_seed.saturating_add(produced);
+        Ok(())
+    }
+}
+
+impl<R> rand_core::CryptoRng for SeededRandom<R> where
+    R: rand_core::SeedableRng<Seed = [u8; 32]> + rand_core::RngCore + rand_core::CryptoRng
+{
+}
+
+/// The numbers of bytes a `MixedRng` has drawn from each of its sources.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct MixingStats {
+    /// The number of bytes drawn from random.org.
+    pub remote_bytes: u64,
+    /// The number of bytes drawn from the local generator.
+    pub local_bytes: u64,
+    /// The number of fills served by the local generator alone, because random.org failed or
+    /// was not called while its circuit was open.
+    pub degraded_fills: u64,
+}
+
+/// A generator which XORs the random.org bytes with the bytes of a local generator, so its
+/// output is at least as unpredictable as the stronger of the two sources. When the service
+/// fails, the output of the local generator is used alone.
+///
+/// Like `FallibleRandom`, the generator stops calling the service after a number of
+/// consecutive failures, until a cooldown is over and a single fill probes whether the
+/// service is back.
+///
+/// # Usage
+///
+/// ```rust,no_run
+/// use rand_core::{OsRng, RngCore};
+/// use randomorg::{rand::MixedRng, Random};
+///
+/// let mut rng = MixedRng::new(Random::new("API KEY HERE"), OsRng);
+/// let mut key = [0u8; 32];
+/// rng.fill_bytes(&mut key);
+/// println!("Drawn bytes: {:?}", rng.stats());
+/// ```
+pub struct MixedRng<L: rand_core::RngCore> {
+    random: crate::Random,
+    local: L,
+    breaker: CircuitBreaker,
+    stats: MixingStats,
+}
+
+impl<L: rand_core::RngCore> MixedRng<L> {
+    /// Creates a generator mixing the bytes of the client with the bytes of `local`.
+    pub fn new(random: crate::Random, local: L) -> MixedRng<L> {
+        MixedRng {
+            random,
+            local,
+            breaker: CircuitBreaker::new(),
+            stats: MixingStats::default(),
+        }
+    }
+
+    /// Sets the number of consecutive failures which opens the circuit. Defaults to 3.
+    #[must_use]
+    pub fn failure_threshold(mut self, failures: u32) -> Self {
+        self.breaker.failure_threshold = failures.max(1);
+        self
+    }
+
+    /// Sets the time the circuit stays open before the service is probed again. Defaults to
+    /// 30 seconds.
+    #[must_use]
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.breaker.cooldown = cooldown;
+        self
+    }
+
+    /// Returns the state of the circuit breaker.
+    #[must_use]
+    pub fn circuit(&self) -> CircuitState {
+        self.breaker.state()
+    }
+
+    /// Returns the numbers of bytes drawn from each source.
+    pub fn stats(&self) -> MixingStats {
+        self.stats
+    }
+}
+
+impl<L: rand_core::RngCore> rand_core::RngCore for MixedRng<L> {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        if let Err(e) = self.try_fill_bytes(dest) {
+            panic!("Error: {}", e);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.local.try_fill_bytes(dest)?;
+        let length = u64::try_from(dest.len()).unwrap_or(u64::MAX);
+        self.stats.local_bytes = self.stats.local_bytes.saturating_add(length);
+        let circuit = self.breaker.state();
+        if circuit == CircuitState::Open {
+            self.stats.degraded_fills += 1;
+            return Ok(());
+        }
+        let mut remote = vec![0u8; dest.len()];
+        match self.random.fill_blob_bytes(&mut remote) {
+            Ok(()) => {
+                self.breaker.succeeded();
+                dest.iter_mut().zip(&remote).for_each(|(d, r)| *d ^= r);
+                self.stats.remote_bytes = self.stats.remote_bytes.saturating_add(length);
+            }
+            Err(e) => {
+                log::warn!("Could not mix the bytes of MixedRng(randomorg) in: {e}");
+                self.breaker.failed(circuit);
+                self.stats.degraded_fills += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<L: rand_core::RngCore + rand_core::CryptoRng> rand_core::CryptoRng for MixedRng<L> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_server::{client, error_result, random_result};
+    use crate::Random;
+    use base64::Engine;
+    use rand_core::RngCore;
+    use std::convert::TryFrom;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn pattern(length: usize) -> Vec<u8> {
+        (0..251).cycle().take(length).collect()
+    }
+
+    #[test]
+    fn test_fill_bytes_of_any_length() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counted = Arc::clone(&calls);
+        let mut random = client(move |request| {
+            counted.fetch_add(1, Ordering::SeqCst);
+            assert_eq!(request["method"], "generateBlobs");
+            assert_eq!(request["params"]["n"], 1);
+            let size = request["params"]["size"].as_u64().unwrap();
+            let bytes = pattern(usize::try_from(size / 8).unwrap());
+            let blob = base64::engine::general_purpose::STANDARD.encode(bytes);
+            random_result(request, &serde_json::json!([blob]), size)
+        });
+
+        for &length in &[0, 1, 3, 1000] {
+            let mut dest = vec![0u8; length];
+            random.try_fill_bytes(&mut dest).unwrap();
+            assert_eq!(dest, pattern(length));
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // Larger than a single blob: 131,072 bytes and then the remaining 1 byte.
+        let mut dest = vec![0u8; 131_073];
+        random.fill_bytes(&mut dest);
+        assert_eq!(&dest[..131_072], pattern(131_072).as_slice());
+        assert_eq!(dest[131_072], 0);
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+
+        let mut expected = [0u8; 8];
+        expected.copy_from_slice(&pattern(8));
+        assert_eq!(random.next_u64(), u64::from_le_bytes(expected));
+        assert_eq!(calls.load(Ordering::SeqCst), 6);
+    }
+
+    #[test]
+    fn test_seeded_random_reseeds() {
+        use super::SeededRandom;
+        use rand_chacha::ChaCha20Rng;
+        use rand_core::SeedableRng;
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counted = Arc::clone(&calls);
+        let random = client(move |request| {
+            counted.fetch_add(1, Ordering::SeqCst);
+            assert_eq!(request["params"]["size"], 256);
+            let blob = base64::engine::general_purpose::STANDARD.encode([7u8; 32]);
+            random_result(request, &serde_json::json!([blob]), 256)
+        });
+        let mut rng = SeededRandom::new(random).reseed_after_bytes(16);
+
+        let mut bytes = [0u8; 16];
+        rng.fill_bytes(&mut bytes);
+        let mut expected = [0u8; 16];
+        ChaCha20Rng::from_seed([7u8; 32]).fill_bytes(&mut expected);
+        assert_eq!(bytes, expected);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // The generator is reseeded with the same seed, so it starts over.
+        rng.fill_bytes(&mut bytes);
+        assert_eq!(bytes, expected);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        rng.next_u32();
+        rng.next_u32();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_mixed_rng_xors_sources_and_degrades() {
+        use super::{CircuitState, MixedRng, MixingStats};
+        use rand_chacha::ChaCha20Rng;
+        use rand_core::SeedableRng;
+
+        let random = client(|request| {
+            let blob = base64::engine::general_purpose::STANDARD.encode([0xffu8; 4]);
+            random_result(request, &serde_json::json!([blob]), 32)
+        });
+        let mut rng = MixedRng::new(random, ChaCha20Rng::from_seed([1u8; 32]));
+        let mut local = ChaCha20Rng::from_seed([1u8; 32]);
+
+        assert_eq!(rng.next_u32(), !local.next_u32());
+        assert_eq!(
+            rng.stats(),
+            MixingStats {
+                remote_bytes: 4,
+                local_bytes: 4,
+                degraded_fills: 0,
+            }
+        );
+
+        // Nothing listens on a port which was just released.
+        let closed = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/", closed.local_addr().unwrap());
+        drop(closed);
+        let mut rng = MixedRng::new(
+            Random::new("API KEY").with_endpoint(endpoint),
+            ChaCha20Rng::from_seed([1u8; 32]),
+        )
+        .failure_threshold(1);
+        let mut local = ChaCha20Rng::from_seed([1u8; 32]);
+
+        assert_eq!(rng.next_u64(), local.next_u64());
+        assert_eq!(rng.stats().remote_bytes, 0);
+        assert_eq!(rng.stats().degraded_fills, 1);
+        // The open circuit serves the local bytes without calling the service.
+        assert_eq!(rng.circuit(), CircuitState::Open);
+        assert_eq!(rng.next_u64(), local.next_u64());
+        assert_eq!(rng.stats().degraded_fills, 2);
+    }
+
+    #[test]
+    fn test_fallible_random_circuit_breaker() {
+        use super::{CircuitState, FallibleRandom};
+        use rand_chacha::ChaCha20Rng;
+        use rand_core::SeedableRng;
+        use std::sync::atomic::AtomicBool;
+        use std::time::Duration;
+
+        let up = Arc::new(AtomicBool::new(false));
+        let service = Arc::clone(&up);
+        let random = client(move |request| {
+            if service.load(Ordering::SeqCst) {
+                let blob = base64::engine::general_purpose::STANDARD.encode([42u8, 0, 0, 0]);
+                random_result(request, &serde_json::json!([blob]), 32)
+            } else {
+                error_result(request, 32000, "Service unavailable.")
+            }
+        });
+        let fallbacks = Arc::new(AtomicUsize::new(0));
+        let counted = Arc::clone(&fallbacks);
+        let mut fallible = FallibleRandom::new(random, ChaCha20Rng::from_seed([1u8; 32]))
+            .failure_threshold(2)
+            .cooldown(Duration::from_millis(50))
+            .on_fallback(move |_| {
+                counted.fetch_add(1, Ordering::SeqCst);
+            });
+
+        fallible.next_u32();
+        assert_eq!(fallible.health().circuit, CircuitState::Closed);
+        fallible.next_u32();
+        assert_eq!(fallible.health().circuit, CircuitState::Open);
+        // The open circuit does not call the service.
+        fallible.next_u32();
+        let health = fallible.health();
+        assert_eq!(health.failures, 2);
+        assert_eq!(health.fallback_values, 3);
+        assert_eq!(fallbacks.load(Ordering::SeqCst), 3);
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(fallible.health().circuit, CircuitState::HalfOpen);
+        up.store(true, Ordering::SeqCst);
+        assert_eq!(fallible.next_u32(), 42);
+        let health = fallible.health();
+        assert_eq!(health.circuit, CircuitState::Closed);
+        assert_eq!(health.remote_values, 1);
+        assert_eq!(health.fallback_values, 3);
+    }
+}
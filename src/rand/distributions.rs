@@ -0,0 +1,327 @@
+//! `rand::distributions::Distribution` adapters, which sample the values on the random.org
+//! side instead of computing them from random bytes. Available with the `distributions`
+//! feature.
+//!
+//! The generator passed to `sample` is not used: the values come from the service, requested
+//! in batches.
+//!
+//! # Usage
+//!
+//! ```rust,no_run
+//! use rand::distributions::Distribution;
+//! use randomorg::rand::distributions::{ServerNormal, ServerUniform};
+//! use randomorg::Random;
+//!
+//! let random = Random::new("API KEY HERE");
+//! let mut rng = rand::thread_rng();
+//! let die = ServerUniform::new_inclusive(&random, 1, 6);
+//! let rolls = die.sample_iter(&mut rng).take(10).collect::<Vec<i32>>();
+//! let normal = ServerNormal::new(&random, 0.5, 0.01).unwrap().batch_size(1000);
+//! let noise = normal.sample(&mut rng);
+//! println!("Rolls: {:?}, noise: {}", rolls, noise);
+//! ```
+
+use crate::{Error, Random, RandomStream, Result};
+use rand_distr::{Normal, NormalError};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// The bounds of the integers the service generates.
+const INTEGER_BOUND: i32 = 1_000_000_000;
+
+/// The default number of values requested from the service at once.
+const DEFAULT_BATCH_SIZE: u16 = 100;
+/// The number of decimal places of the fractions uniform floating point values come from.
+const DECIMAL_PLACES: u8 = 14;
+/// The number of significant digits of the gaussians normal values come from.
+const SIGNIFICANT_DIGITS: u8 = 14;
+
+type Fetch<X> = Arc<dyn Fn(u16) -> Result<Vec<X>> + Send + Sync>;
+
+/// Hands out the values of a stream of batches, starting the stream over after an error.
+struct Sampler<X> {
+    fetch: Fetch<X>,
+    batch_size: u16,
+    values: Mutex<Option<RandomStream<X>>>,
+}
+
+impl<X: Send + 'static> Sampler<X> {
+    fn new<F>(fetch: F) -> Sampler<X>
+    where
+        F: Fn(u16) -> Result<Vec<X>> + Send + Sync + 'static,
+    {
+        Sampler {
+            fetch: Arc::new(fetch),
+            batch_size: DEFAULT_BATCH_SIZE,
+            values: Mutex::new(None),
+        }
+    }
+
+    fn batch_size(mut self, batch_size: u16) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    fn sample(&self) -> Result<X> {
+        let mut values = self.values.lock().unwrap_or_else(PoisonError::into_inner);
+        let stream = values.get_or_insert_with(|| {
+            let fetch = Arc::clone(&self.fetch);
+            let batch_size = self.batch_size;
+            RandomStream::new(batch_size, move || fetch(batch_size))
+        });
+        let value = stream
+            .next()
+            .unwrap_or(Err(Error::Rest("The service returned no values.")));
+        if value.is_err() {
+            *values = None;
+        }
+        value
+    }
+}
+
+/// A uniform distribution of the values generated by random.org: integers come from
+/// `generateIntegers` and floating point values from `generateDecimalFractions`.
+pub struct ServerUniform<X> {
+    sampler: Sampler<X>,
+}
+
+impl<X: Send + 'static> ServerUniform<X> {
+    /// Sets the number of values requested from the service at once. Defaults to 100.
+    #[must_use]
+    pub fn batch_size(mut self, batch_size: u16) -> Self {
+        self.sampler = self.sampler.batch_size(batch_size);
+        self
+    }
+
+    /// Samples a value, returning the error of the service instead of panicking.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the service if a new batch of values can't be requested.
+    pub fn try_sample(&self) -> Result<X> {
+        self.sampler.sample()
+    }
+}
+
+/// A function requesting a number of uniformly distributed values from the service.
+pub type UniformFetch<X> = Box<dyn Fn(u16) -> Result<Vec<X>> + Send + Sync>;
+
+/// The types of values which random.org samples uniformly, for `ServerUniform`.
+pub trait SampleServerUniform: Sized + Send + 'static {
+    /// Returns a function requesting values over the `[low, high)` range from the client, or
+    /// over the `[low, high]` range when `inclusive` is set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is empty, like `rand::distributions::Uniform`, or out of the range
+    /// of the values the service generates.
+    fn fetch_uniform(client: Random, low: Self, high: Self, inclusive: bool) -> UniformFetch<Self>;
+}
+
+impl SampleServerUniform for i32 {
+    /// Integers come from `generateIntegers`, which generates them within [-1e9; 1e9] range.
+    fn fetch_uniform(client: Random, low: i32, high: i32, inclusive: bool) -> UniformFetch<i32> {
+        let last = if inclusive {
+            high
+        } else {
+            high.saturating_sub(1)
+        };
+        assert!(
+            (-INTEGER_BOUND..=INTEGER_BOUND).contains(&low)
+                && (-INTEGER_BOUND..=INTEGER_BOUND).contains(&last),
+            "ServerUniform called with bounds out of the [-1e9, 1e9] range"
+        );
+        let high = if inclusive {
+            assert!(low <= high, "ServerUniform called with `low > high`");
+            high
+        } else {
+            assert!(low < high, "ServerUniform called with `low >= high`");
+            high - 1
+        };
+        Box::new(move |limit| {
+            Ok(client
+                .generate_integers(low, high, limit, true)?
+                .result
+                .random
+                .data)
+        })
+    }
+}
+
+impl SampleServerUniform for f64 {
+    /// Floating point values are the fractions of `generateDecimalFractions` with 14 decimal
+    /// places, within [0; 1] range, scaled to the range. For the `[low, high)` range, the
+    /// values reaching `high` are drawn again.
+    fn fetch_uniform(client: Random, low: f64, high: f64, inclusive: bool) -> UniformFetch<f64> {
+        assert!(
+            low.is_finite() && high.is_finite() && (low < high || inclusive && low <= high),
+            "ServerUniform called with an empty range or non-finite bounds"
+        );
+        Box::new(move |limit| loop {
+            let values = client
+                .generate_decimal_fractions(limit, DECIMAL_PLACES)?
+                .result
+                .random
+                .data
+                .into_iter()
+                .map(|x| x.mul_add(high - low, low))
+                .filter(|&x| inclusive || x < high)
+                .collect::<Vec<f64>>();
+            if !values.is_empty() {
+                return Ok(values);
+            }
+        })
+    }
+}
+
+impl<X: SampleServerUniform> ServerUniform<X> {
+    /// Creates a uniform distribution over the `[low, high)` range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `low >= high`, like `rand::distributions::Uniform::new`, or if integer bounds
+    /// are out of the [-1e9; 1e9] range of the service.
+    pub fn new(client: &Random, low: X, high: X) -> ServerUniform<X> {
+        ServerUniform {
+            sampler: Sampler::new(X::fetch_uniform(client.clone(), low, high, false)),
+        }
+    }
+
+    /// Creates a uniform distribution over the `[low, high]` range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `low > high`, like `rand::distributions::Uniform::new_inclusive`, or if
+    /// integer bounds are out of the [-1e9; 1e9] range of the service.
+    pub fn new_inclusive(client: &Random, low: X, high: X) -> ServerUniform<X> {
+        ServerUniform {
+            sampler: Sampler::new(X::fetch_uniform(client.clone(), low, high, true)),
+        }
+    }
+}
+
+impl<X: SampleServerUniform> ::rand::distributions::Distribution<X> for ServerUniform<X> {
+    fn sample<R: ::rand::Rng + ?Sized>(&self, _rng: &mut R) -> X {
+        self.try_sample()
+            .unwrap_or_else(|e| panic!("Could not sample a value: {}", e))
+    }
+}
+
+/// A normal distribution of the values generated by random.org with `generateGaussians`.
+///
+/// The service generates standard gaussians with 14 significant digits, which are scaled to
+/// the mean and the standard deviation of the distribution.
+pub struct ServerNormal {
+    sampler: Sampler<f64>,
+}
+
+impl ServerNormal {
+    /// Creates a normal distribution, validating the parameters like `rand_distr::Normal::new`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the `NormalError` of `rand_distr` if the standard deviation is negative or not
+    /// finite.
+    pub fn new(client: &Random, mean: f64, std_dev: f64) -> std::result::Result<Self, NormalError> {
+        Ok(ServerNormal::from_normal(
+            client,
+            &Normal::new(mean, std_dev)?,
+        ))
+    }
+
+    /// Creates a distribution with the mean and the standard deviation of `normal`.
+    #[must_use]
+    pub fn from_normal(client: &Random, normal: &Normal<f64>) -> ServerNormal {
+        let client = client.clone();
+        let (mean, std_dev) = (normal.mean(), normal.std_dev());
+        ServerNormal {
+            sampler: Sampler::new(move |limit| {
+                let gaussians = client
+                    .generate_gaussians(limit, 0.0, 1.0, SIGNIFICANT_DIGITS)?
+                    .result
+                    .random
+                    .data;
+                Ok(gaussians
+                    .into_iter()
+                    .map(|x| x.mul_add(std_dev, mean))
+                    .collect())
+            }),
+        }
+    }
+
+    /// Sets the number of values requested from the service at once. Defaults to 100.
+    #[must_use]
+    pub fn batch_size(mut self, batch_size: u16) -> Self {
+        self.sampler = self.sampler.batch_size(batch_size);
+        self
+    }
+
+    /// Samples a value, returning the error of the service instead of panicking.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the service if a new batch of values can't be requested.
+    pub fn try_sample(&self) -> Result<f64> {
+        self.sampler.sample()
+    }
+}
+
+impl ::rand::distributions::Distribution<f64> for ServerNormal {
+    fn sample<R: ::rand::Rng + ?Sized>(&self, _rng: &mut R) -> f64 {
+        self.try_sample()
+            .unwrap_or_else(|e| panic!("Could not sample a gaussian: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ServerNormal, ServerUniform};
+    use crate::test_server::{client, random_result};
+    use crate::Random;
+    use rand::distributions::Distribution;
+
+    #[test]
+    fn test_distributions_sample_server_values() {
+        let random = client(|request| {
+            let params = &request["params"];
+            let n = params["n"].as_u64().unwrap();
+            let data = match request["method"].as_str().unwrap() {
+                "generateIntegers" => {
+                    assert_eq!((&params["min"], &params["max"]), (&1.into(), &6.into()));
+                    serde_json::json!((1..=n).map(|i| i % 6 + 1).collect::<Vec<u64>>())
+                }
+                "generateDecimalFractions" => serde_json::json!([1.0, 0.25, 1.0]),
+                "generateGaussians" => {
+                    assert_eq!(
+                        (&params["mean"], &params["standardDeviation"]),
+                        (&0.0.into(), &1.0.into())
+                    );
+                    serde_json::json!(vec![-2.0; 3])
+                }
+                method => panic!("Unexpected method {}", method),
+            };
+            random_result(request, &data, 10)
+        });
+        let mut rng = rand::thread_rng();
+
+        let die = ServerUniform::new(&random, 1, 7).batch_size(4);
+        let rolls = die.sample_iter(&mut rng).take(6).collect::<Vec<i32>>();
+        assert_eq!(rolls, vec![2, 3, 4, 5, 2, 3]);
+
+        // A fraction of 1 reaches the excluded upper bound, so it is skipped.
+        let uniform = ServerUniform::new(&random, 10.0, 20.0).batch_size(3);
+        assert!((uniform.sample(&mut rng) - 12.5).abs() < 1e-9);
+        let uniform = ServerUniform::new_inclusive(&random, 10.0, 20.0).batch_size(3);
+        assert!((uniform.sample(&mut rng) - 20.0).abs() < 1e-9);
+
+        let normal = ServerNormal::new(&random, 0.5, 0.01).unwrap().batch_size(3);
+        assert!((normal.sample(&mut rng) - 0.48).abs() < 1e-9);
+        assert!(ServerNormal::new(&random, 0.0, f64::NAN).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "out of the [-1e9, 1e9] range")]
+    fn test_uniform_integers_within_service_range() {
+        let random = Random::new("API KEY");
+        let _ = ServerUniform::new_inclusive(&random, 0, 1_000_000_001);
+    }
+}
@@ -0,0 +1,186 @@
+//! A `std::io::Read` stream of random bytes, generated by random.org as blobs.
+//!
+//! # Usage
+//!
+//! ```rust,no_run
+//! use randomorg::{Random, RandomReader};
+//! use std::io::Read;
+//!
+//! let mut reader = RandomReader::new(Random::new("API KEY HERE"));
+//! let mut key = [0u8; 32];
+//! reader.read_exact(&mut key).unwrap();
+//! println!("Key: {:?}", key);
+//! ```
+
+use crate::request_builders::MAX_BLOBS_SIZE;
+use crate::{Error, Random, Result};
+use base64::Engine;
+use std::io::{self, BufRead, Read};
+
+/// The default size of the blobs the reader requests, in bits.
+const DEFAULT_BLOB_SIZE: u32 = 4096;
+
+/// Decodes a base64-encoded blob returned by the service.
+pub(crate) fn decode_blob(blob: &str) -> Result<Vec<u8>> {
+    Ok(base64::engine::general_purpose::STANDARD.decode(blob)?)
+}
+
+/// A reader of random bytes, which requests a new blob from the service whenever all the
+/// bytes of the previous one were read.
+pub struct RandomReader {
+    fetch: Box<dyn FnMut(u32) -> Result<Vec<u8>> + Send>,
+    blob_size: u32,
+    buffer: Vec<u8>,
+    position: usize,
+}
+
+impl RandomReader {
+    /// Creates a reader of the blobs generated by the client.
+    #[must_use]
+    pub fn new(client: Random) -> RandomReader {
+        RandomReader::with_fetch(move |size| {
+            let blobs = client.generate_blobs(1, size)?.result.random.data;
+            blobs.iter().try_fold(Vec::new(), |mut bytes, blob| {
+                bytes.extend(decode_blob(blob)?);
+                Ok(bytes)
+            })
+        })
+    }
+
+    /// Creates a reader of the blobs returned by `fetch` for a requested blob size.
+    pub(crate) fn with_fetch<F>(fetch: F) -> RandomReader
+    where
+        F: FnMut(u32) -> Result<Vec<u8>> + Send + 'static,
+    {
+        RandomReader {
+            fetch: Box::new(fetch),
+            blob_size: DEFAULT_BLOB_SIZE,
+            buffer: Vec::new(),
+            position: 0,
+        }
+    }
+
+    /// Sets the size of the blobs the reader requests, in bytes. Defaults to 512 bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Rest` if `bytes` is not within the [1; 131072] range.
+    pub fn blob_size(mut self, bytes: u32) -> Result<Self> {
+        match bytes.checked_mul(8) {
+            Some(size) if size > 0 && size <= MAX_BLOBS_SIZE => {
+                self.blob_size = size;
+                Ok(self)
+            }
+            _ => Err(Error::Rest(
+                "The blob size must be within the [1, 131072] bytes range.",
+            )),
+        }
+    }
+}
+
+impl Read for RandomReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let read = available.len().min(buf.len());
+        buf[..read].copy_from_slice(&available[..read]);
+        self.consume(read);
+        Ok(read)
+    }
+}
+
+impl BufRead for RandomReader {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.position >= self.buffer.len() {
+            let buffer = (self.fetch)(self.blob_size).map_err(io::Error::other)?;
+            if buffer.len() * 8 != self.blob_size as usize {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "The service returned a blob of a wrong size.",
+                ));
+            }
+            self.buffer = buffer;
+            self.position = 0;
+        }
+        Ok(&self.buffer[self.position..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.position = (self.position + amount).min(self.buffer.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_blob, RandomReader};
+    use crate::test_server::{client, random_result};
+    use std::io::{BufRead, Read};
+
+    #[test]
+    fn test_decode_blob() {
+        assert_eq!(decode_blob("aQ==").unwrap(), vec![0x69]);
+        assert_eq!(decode_blob("AAEC/w==").unwrap(), vec![0, 1, 2, 255]);
+        assert!(decode_blob("not base64!").is_err());
+    }
+
+    #[test]
+    fn test_reader_refills_in_blob_sized_chunks() {
+        let mut next = 0u8;
+        let mut reader = RandomReader::with_fetch(move |size| {
+            let bytes = (0..size / 8)
+                .map(|_| {
+                    next = next.wrapping_add(1);
+                    next
+                })
+                .collect();
+            Ok(bytes)
+        })
+        .blob_size(3)
+        .unwrap();
+
+        let mut bytes = [0u8; 8];
+        reader.read_exact(&mut bytes).unwrap();
+        assert_eq!(bytes, [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(reader.fill_buf().unwrap(), &[9]);
+    }
+
+    #[test]
+    fn test_reader_rejects_wrong_sizes() {
+        let reader = || RandomReader::with_fetch(|_| Ok(vec![1, 2]));
+        assert!(reader().blob_size(0).is_err());
+        assert!(reader().blob_size(131_073).is_err());
+        assert!(reader().blob_size(131_072).is_ok());
+
+        let mut reader = reader().blob_size(3).unwrap();
+        let error = reader.fill_buf().unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        let mut empty = RandomReader::with_fetch(|_| Ok(Vec::new()));
+        assert!(empty.fill_buf().is_err());
+    }
+
+    #[test]
+    fn test_reader_decodes_blobs_of_the_service() {
+        let client = client(|request| {
+            assert_eq!(request["method"], "generateBlobs");
+            assert_eq!(request["params"]["size"], 16);
+            random_result(request, &serde_json::json!(["AP8="]), 16)
+        });
+        let mut reader = RandomReader::new(client).blob_size(2).unwrap();
+
+        let mut bytes = [0u8; 5];
+        reader.read_exact(&mut bytes).unwrap();
+        assert_eq!(bytes, [0, 255, 0, 255, 0]);
+    }
+
+    #[test]
+    fn test_reader_rejects_blobs_of_a_wrong_size() {
+        let client = client(|request| {
+            assert_eq!(request["params"]["size"], 16);
+            random_result(request, &serde_json::json!(["AAH/"]), 16)
+        });
+        let mut reader = RandomReader::new(client).blob_size(2).unwrap();
+
+        let mut bytes = [0u8; 2];
+        let error = reader.read_exact(&mut bytes).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
@@ -1,12 +1,28 @@
+use crate::params::{
+    blobs_bits, decimal_fractions_bits, gaussians_bits, integers_bits, strings_bits, uuids_bits,
+};
 use crate::{
-    AllowedCharacters, GenerateBlobsResult, GenerateDecimalFractionsResult,
-    GenerateGaussiansResult, GenerateIntegersResult, GenerateStringsResult, GenerateUUIDsResult,
-    Random, Response, Result,
+    AllowedCharacters, Error, GenerateBlobsResult, GenerateDecimalFractionsExactResult,
+    GenerateDecimalFractionsResult, GenerateGaussiansExactResult, GenerateGaussiansResult,
+    GenerateIntegersResult, GenerateStringsResult, GenerateUUIDsResult, Random, RandomResult,
+    RandomStream, Response, Result,
 };
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+
+/// The maximum number of values the service generates in a single call.
+const MAX_LIMIT: u16 = 10_000;
+/// The maximum number of UUIDs the service generates in a single call.
+const MAX_UUIDS_LIMIT: u16 = 1_000;
+/// The maximum number of blobs the service generates in a single call.
+const MAX_BLOBS_LIMIT: u16 = 100;
+/// The maximum total size of the blobs the service generates in a single call, in bits.
+pub(crate) const MAX_BLOBS_SIZE: u32 = 1_048_576;
 
 macro_rules! builder {
     ($field:ident, $field_type:ty) => {
         /// A builder method
+        #[must_use]
         pub fn $field(mut self, $field: $field_type) -> Self {
             self.$field = $field;
             self
@@ -14,207 +30,732 @@ macro_rules! builder {
     };
 }
 
+macro_rules! count_builder {
+    () => {
+        /// A builder method
+        #[must_use]
+        pub fn limit(mut self, limit: u16) -> Self {
+            self.count = u64::from(limit);
+            self
+        }
+
+        /// A builder method. Sets how many values to collect, at least one, which may be more
+        /// than the service generates in a single call: such a request is split into several
+        /// calls.
+        #[must_use]
+        pub fn count(mut self, count: u64) -> Self {
+            self.count = count;
+            self
+        }
+    };
+}
+
+/// Performs as many calls of at most `max_limit` values as needed to get `count` values,
+/// respecting the advisory delay between them, and returns their responses.
+///
+/// The calls expected to use `estimated_bits` together are checked against the budget of the
+/// client before the first one is made, so that no value is paid for and thrown away.
+fn chunks<T, F>(
+    client: &Random,
+    count: u64,
+    estimated_bits: u64,
+    max_limit: u16,
+    mut call: F,
+) -> Result<Vec<Response<RandomResult<T>>>>
+where
+    F: FnMut(u16) -> Result<Response<RandomResult<T>>>,
+{
+    if count == 0 {
+        return Err(Error::Rest("A request must collect at least one value."));
+    }
+    client.check_budget(estimated_bits, count.div_ceil(u64::from(max_limit)))?;
+    let next_limit = |left: u64| u16::try_from(left.min(u64::from(max_limit))).unwrap_or(max_limit);
+    let mut responses: Vec<Response<RandomResult<T>>> = Vec::new();
+    let mut left = count;
+    while left > 0 {
+        if let Some(previous) = responses.last() {
+            std::thread::sleep(std::time::Duration::from_millis(
+                previous.result.advisory_delay,
+            ));
+        }
+        let limit = next_limit(left);
+        responses.push(call(limit)?);
+        left -= u64::from(limit);
+    }
+    Ok(responses)
+}
+
+/// Concatenates the random data of the responses into the first one.
+fn merged<T>(responses: Vec<Response<RandomResult<T>>>) -> Result<Response<RandomResult<T>>> {
+    let mut responses = responses.into_iter();
+    let mut response = responses
+        .next()
+        .ok_or(Error::Rest("A request must collect at least one value."))?;
+    responses.for_each(|later| response.append(later));
+    Ok(response)
+}
+
+/// Performs the calls of `chunks` and concatenates their random data.
+fn chunked<T, F>(
+    client: &Random,
+    count: u64,
+    estimated_bits: u64,
+    max_limit: u16,
+    call: F,
+) -> Result<Response<RandomResult<T>>>
+where
+    F: FnMut(u16) -> Result<Response<RandomResult<T>>>,
+{
+    merged(chunks(client, count, estimated_bits, max_limit, call)?)
+}
+
+/// Returns the number of values to request per page of a stream.
+fn page_size(count: u64, max_limit: u16) -> u16 {
+    u16::try_from(count.clamp(1, u64::from(max_limit))).unwrap_or(max_limit)
+}
+
+macro_rules! into_iter {
+    ($request:ident, $item:ty) => {
+        impl IntoIterator for $request<'_> {
+            type Item = Result<$item>;
+            type IntoIter = RandomStream<$item>;
+
+            fn into_iter(self) -> RandomStream<$item> {
+                self.stream()
+            }
+        }
+    };
+}
+
+/// Maps the indices picked among the values not taken yet to these values, keeping their order.
+fn untaken(min: i64, taken: &BTreeSet<i64>, picked: &[i32]) -> Vec<i64> {
+    let mut order = (0..picked.len()).collect::<Vec<usize>>();
+    order.sort_unstable_by_key(|&i| picked[i]);
+    let mut values = vec![0; picked.len()];
+    let mut taken = taken.iter().peekable();
+    let mut skipped = 0i64;
+    for i in order {
+        let index = i64::from(picked[i]) - min;
+        while taken.next_if(|&&t| t <= min + index + skipped).is_some() {
+            skipped += 1;
+        }
+        values[i] = min + index + skipped;
+    }
+    values
+}
+
 /// A lazy integers request (builder)
 pub struct RequestIntegers<'a> {
     client: &'a Random,
     min: i32,
     max: i32,
-    limit: u16,
+    count: u64,
     replacement: bool,
 }
 
 impl<'a> RequestIntegers<'a> {
     /// Creates a lazy integers request (builder)
-    pub fn new(client: &'a Random) -> RequestIntegers {
+    #[must_use]
+    pub fn new(client: &'a Random) -> RequestIntegers<'a> {
         RequestIntegers {
             client,
             min: 0i32,
             max: 100i32,
-            limit: 10,
+            count: 10,
             replacement: true,
         }
     }
 
     builder!(min, i32);
     builder!(max, i32);
-    builder!(limit, u16);
+    count_builder!();
     builder!(replacement, bool);
+
+    /// Returns the expected `bitsUsed` of the request.
+    #[must_use]
+    pub fn estimated_bits(&self) -> u64 {
+        integers_bits(self.min, self.max, self.count, self.replacement)
+    }
+
+    /// Picks unique integers across all the calls: every call after the first one picks the
+    /// indices of the integers which have not been taken yet. Returns the integers along with
+    /// the responses of the calls, as the service returned them.
+    pub(crate) fn collect_unique_calls(
+        &self,
+    ) -> Result<(Vec<i32>, Vec<Response<GenerateIntegersResult>>)> {
+        let min = i64::from(self.min);
+        let range = i64::from(self.max) - min + 1;
+        if i64::try_from(self.count).map_or(true, |count| count > range) {
+            return Err(Error::Rest(
+                "Can't pick more unique integers than the range contains.",
+            ));
+        }
+        let mut taken = BTreeSet::new();
+        let mut values = Vec::new();
+        let responses = chunks(
+            self.client,
+            self.count,
+            self.estimated_bits(),
+            MAX_LIMIT,
+            |limit| {
+                let untaken_max = min + range - 1 - i64::try_from(taken.len()).unwrap_or(range);
+                let response = self.client.generate_integers(
+                    self.min,
+                    i32::try_from(untaken_max).unwrap_or(self.max),
+                    limit,
+                    false,
+                )?;
+                let picked = untaken(min, &taken, &response.result.random.data);
+                taken.extend(picked.iter().copied());
+                values.extend(
+                    picked
+                        .into_iter()
+                        .map(|v| i32::try_from(v).unwrap_or(self.max)),
+                );
+                Ok(response)
+            },
+        )?;
+        Ok((values, responses))
+    }
+
+    /// Picks unique integers across all the calls, merging their responses into one which
+    /// carries the integers.
+    fn collect_unique(&self) -> Result<Response<GenerateIntegersResult>> {
+        let (values, responses) = self.collect_unique_calls()?;
+        let mut response = merged(responses)?;
+        response.result.random.data = values;
+        Ok(response)
+    }
 }
 
 /// Terminators
-impl<'a> RequestIntegers<'a> {
+impl RequestIntegers<'_> {
     /// Collect the random integers (performs the request)
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of the calls to `Random::generate_integers`.
     pub fn collect<T: From<Response<GenerateIntegersResult>>>(self) -> Result<T> {
-        Ok(T::from(self.client.generate_integers(
-            self.min,
-            self.max,
-            self.limit,
-            self.replacement,
+        if !self.replacement {
+            return Ok(T::from(self.collect_unique()?));
+        }
+        Ok(T::from(chunked(
+            self.client,
+            self.count,
+            self.estimated_bits(),
+            MAX_LIMIT,
+            |limit| {
+                self.client
+                    .generate_integers(self.min, self.max, limit, self.replacement)
+            },
         )?))
     }
+
+    /// Returns an endless iterator over the random integers, requesting them page by page,
+    /// `limit` integers at a time. With `replacement(false)` the integers are unique only
+    /// within a page.
+    #[must_use]
+    pub fn stream(self) -> RandomStream<i32> {
+        let client = self.client.clone();
+        let (min, max, replacement) = (self.min, self.max, self.replacement);
+        let limit = page_size(self.count, MAX_LIMIT);
+        RandomStream::new(limit, move || {
+            Ok(client
+                .generate_integers(min, max, limit, replacement)?
+                .result
+                .random
+                .data)
+        })
+    }
 }
 
+into_iter!(RequestIntegers, i32);
+
 /// A lazy decimal fractions request (builder)
 pub struct RequestDecimalFractions<'a> {
     client: &'a Random,
-    limit: u16,
+    count: u64,
     decimal_places: u8,
 }
 
 impl<'a> RequestDecimalFractions<'a> {
     /// Creates a lazy decimal fractions request (builder)
-    pub fn new(client: &'a Random) -> RequestDecimalFractions {
+    #[must_use]
+    pub fn new(client: &'a Random) -> RequestDecimalFractions<'a> {
         RequestDecimalFractions {
             client,
-            limit: 10u16,
+            count: 10,
             decimal_places: 4u8,
         }
     }
 
-    builder!(limit, u16);
+    count_builder!();
     builder!(decimal_places, u8);
+
+    /// Returns the expected `bitsUsed` of the request.
+    #[must_use]
+    pub fn estimated_bits(&self) -> u64 {
+        decimal_fractions_bits(self.count, self.decimal_places)
+    }
 }
 
 /// Terminators
-impl<'a> RequestDecimalFractions<'a> {
+impl RequestDecimalFractions<'_> {
     /// Collect the random decimal fractions (performs the request)
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of the calls to `Random::generate_decimal_fractions`.
     pub fn collect<T: From<Response<GenerateDecimalFractionsResult>>>(self) -> Result<T> {
-        Ok(T::from(self.client.generate_decimal_fractions(
-            self.limit,
-            self.decimal_places,
+        Ok(T::from(chunked(
+            self.client,
+            self.count,
+            self.estimated_bits(),
+            MAX_LIMIT,
+            |limit| {
+                self.client
+                    .generate_decimal_fractions(limit, self.decimal_places)
+            },
+        )?))
+    }
+
+    /// Collect the random decimal fractions keeping every digit of them (performs the request)
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of the calls to `Random::generate_decimal_fractions_exact`.
+    pub fn collect_exact<T: From<Response<GenerateDecimalFractionsExactResult>>>(
+        self,
+    ) -> Result<T> {
+        Ok(T::from(chunked(
+            self.client,
+            self.count,
+            self.estimated_bits(),
+            MAX_LIMIT,
+            |limit| {
+                self.client
+                    .generate_decimal_fractions_exact(limit, self.decimal_places)
+            },
         )?))
     }
+
+    /// Returns an endless iterator over the random decimal fractions, requesting them page by
+    /// page, `limit` decimal fractions at a time.
+    #[must_use]
+    pub fn stream(self) -> RandomStream<f64> {
+        let client = self.client.clone();
+        let decimal_places = self.decimal_places;
+        let limit = page_size(self.count, MAX_LIMIT);
+        RandomStream::new(limit, move || {
+            Ok(client
+                .generate_decimal_fractions(limit, decimal_places)?
+                .result
+                .random
+                .data)
+        })
+    }
 }
 
+into_iter!(RequestDecimalFractions, f64);
+
 /// A lazy gaussians request (builder)
 pub struct RequestGaussians<'a> {
     client: &'a Random,
-    limit: u16,
-    mean: i32,
-    standard_deviation: i32,
+    count: u64,
+    mean: f64,
+    standard_deviation: f64,
     significant_digits: u8,
 }
 
 impl<'a> RequestGaussians<'a> {
     /// Creates a lazy gaussians request (builder)
-    pub fn new(client: &'a Random) -> RequestGaussians {
+    #[must_use]
+    pub fn new(client: &'a Random) -> RequestGaussians<'a> {
         RequestGaussians {
             client,
-            limit: 10u16,
-            mean: 0i32,
-            standard_deviation: 0i32,
+            count: 10,
+            mean: 0.0,
+            standard_deviation: 0.0,
             significant_digits: 0u8,
         }
     }
 
-    builder!(limit, u16);
-    builder!(mean, i32);
-    builder!(standard_deviation, i32);
+    count_builder!();
+    /// Sets the distribution's mean, a real number within the [-1e6, 1e6] range. Integers
+    /// are accepted as well.
+    #[must_use]
+    pub fn mean<M: Into<f64>>(mut self, mean: M) -> Self {
+        self.mean = mean.into();
+        self
+    }
+
+    /// Sets the distribution's standard deviation, a real number within the [-1e6, 1e6] range.
+    /// Integers are accepted as well.
+    #[must_use]
+    pub fn standard_deviation<D: Into<f64>>(mut self, standard_deviation: D) -> Self {
+        self.standard_deviation = standard_deviation.into();
+        self
+    }
     builder!(significant_digits, u8);
+
+    /// Returns the expected `bitsUsed` of the request.
+    #[must_use]
+    pub fn estimated_bits(&self) -> u64 {
+        gaussians_bits(self.count, self.significant_digits)
+    }
 }
 
 /// Terminators
-impl<'a> RequestGaussians<'a> {
+impl RequestGaussians<'_> {
     /// Collect the random gaussians (performs the request)
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of the calls to `Random::generate_gaussians`.
     pub fn collect<T: From<Response<GenerateGaussiansResult>>>(self) -> Result<T> {
-        Ok(T::from(self.client.generate_gaussians(
-            self.limit,
-            self.mean,
-            self.standard_deviation,
-            self.significant_digits,
+        Ok(T::from(chunked(
+            self.client,
+            self.count,
+            self.estimated_bits(),
+            MAX_LIMIT,
+            |limit| {
+                self.client.generate_gaussians(
+                    limit,
+                    self.mean,
+                    self.standard_deviation,
+                    self.significant_digits,
+                )
+            },
         )?))
     }
+
+    /// Collect the random gaussians keeping every digit of them (performs the request)
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of the calls to `Random::generate_gaussians_exact`.
+    pub fn collect_exact<T: From<Response<GenerateGaussiansExactResult>>>(self) -> Result<T> {
+        Ok(T::from(chunked(
+            self.client,
+            self.count,
+            self.estimated_bits(),
+            MAX_LIMIT,
+            |limit| {
+                self.client.generate_gaussians_exact(
+                    limit,
+                    self.mean,
+                    self.standard_deviation,
+                    self.significant_digits,
+                )
+            },
+        )?))
+    }
+
+    /// Returns an endless iterator over the random gaussians, requesting them page by page,
+    /// `limit` gaussians at a time.
+    #[must_use]
+    pub fn stream(self) -> RandomStream<f64> {
+        let client = self.client.clone();
+        let (mean, standard_deviation, significant_digits) =
+            (self.mean, self.standard_deviation, self.significant_digits);
+        let limit = page_size(self.count, MAX_LIMIT);
+        RandomStream::new(limit, move || {
+            Ok(client
+                .generate_gaussians(limit, mean, standard_deviation, significant_digits)?
+                .result
+                .random
+                .data)
+        })
+    }
 }
 
+into_iter!(RequestGaussians, f64);
+
 /// A lazy strings request (builder)
 pub struct RequestStrings<'a> {
     client: &'a Random,
-    limit: u16,
+    count: u64,
     length: u8,
     characters: AllowedCharacters,
 }
 
 impl<'a> RequestStrings<'a> {
     /// Creates a lazy strings request (builder)
-    pub fn new(client: &'a Random) -> RequestStrings {
-        use std::collections::BTreeSet;
-
+    #[must_use]
+    pub fn new(client: &'a Random) -> RequestStrings<'a> {
         RequestStrings {
             client,
-            limit: 10u16,
+            count: 10,
             length: 0u8,
             characters: AllowedCharacters("0123456789abcdef".chars().collect::<BTreeSet<char>>()),
         }
     }
 
-    builder!(limit, u16);
+    count_builder!();
     builder!(length, u8);
     builder!(characters, AllowedCharacters);
+
+    /// Returns the expected `bitsUsed` of the request.
+    #[must_use]
+    pub fn estimated_bits(&self) -> u64 {
+        strings_bits(self.count, self.length, self.characters.0.len())
+    }
 }
 
 /// Terminators
-impl<'a> RequestStrings<'a> {
+impl RequestStrings<'_> {
     /// Collect the random strings (performs the request)
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of the calls to `Random::generate_strings`.
     pub fn collect<T: From<Response<GenerateStringsResult>>>(self) -> Result<T> {
-        Ok(T::from(self.client.generate_strings(
-            self.limit,
-            self.length,
-            self.characters,
+        Ok(T::from(chunked(
+            self.client,
+            self.count,
+            self.estimated_bits(),
+            MAX_LIMIT,
+            |limit| {
+                self.client
+                    .generate_strings(limit, self.length, self.characters.clone())
+            },
         )?))
     }
+
+    /// Returns an endless iterator over the random strings, requesting them page by page,
+    /// `limit` strings at a time.
+    #[must_use]
+    pub fn stream(self) -> RandomStream<String> {
+        let client = self.client.clone();
+        let (length, characters) = (self.length, self.characters);
+        let limit = page_size(self.count, MAX_LIMIT);
+        RandomStream::new(limit, move || {
+            Ok(client
+                .generate_strings(limit, length, characters.clone())?
+                .result
+                .random
+                .data)
+        })
+    }
 }
 
+into_iter!(RequestStrings, String);
+
 /// A lazy UUIDs request (builder)
 pub struct RequestUUIDs<'a> {
     client: &'a Random,
-    limit: u16,
+    count: u64,
 }
 
 impl<'a> RequestUUIDs<'a> {
     /// Creates a lazy UUIDs request (builder)
-    pub fn new(client: &'a Random) -> RequestUUIDs {
-        RequestUUIDs {
-            client,
-            limit: 10u16,
-        }
+    #[must_use]
+    pub fn new(client: &'a Random) -> RequestUUIDs<'a> {
+        RequestUUIDs { client, count: 10 }
     }
 
-    builder!(limit, u16);
+    count_builder!();
+
+    /// Returns the expected `bitsUsed` of the request.
+    #[must_use]
+    pub fn estimated_bits(&self) -> u64 {
+        uuids_bits(self.count)
+    }
 }
 
 /// Terminators
-impl<'a> RequestUUIDs<'a> {
+impl RequestUUIDs<'_> {
     /// Collect the random UUIDs (performs the request)
-    pub fn collect<T: From<Response<GenerateUUIDsResult>>>(self) -> Result<T> {
-        Ok(T::from(self.client.generate_uuids(self.limit)?))
+    ///
+    /// With the `uuid` feature the UUIDs may be collected as `Vec<uuid::Uuid>`, which fails
+    /// with `Error::Rest` if the service returns anything but version 4 RFC 4122 UUIDs.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of the calls to `Random::generate_uuids`, or of the conversion of
+    /// the UUIDs.
+    pub fn collect<T>(self) -> Result<T>
+    where
+        T: TryFrom<Response<GenerateUUIDsResult>>,
+        Error: From<T::Error>,
+    {
+        Ok(T::try_from(chunked(
+            self.client,
+            self.count,
+            self.estimated_bits(),
+            MAX_UUIDS_LIMIT,
+            |limit| self.client.generate_uuids(limit),
+        )?)?)
+    }
+
+    /// Returns an endless iterator over the random UUIDs, requesting them page by page,
+    /// `limit` UUIDs at a time.
+    #[must_use]
+    pub fn stream(self) -> RandomStream<String> {
+        let client = self.client.clone();
+        let limit = page_size(self.count, MAX_UUIDS_LIMIT);
+        RandomStream::new(limit, move || {
+            Ok(client.generate_uuids(limit)?.result.random.data)
+        })
     }
 }
 
+into_iter!(RequestUUIDs, String);
+
 /// A lazy blobs request (builder)
 pub struct RequestBlobs<'a> {
     client: &'a Random,
-    limit: u16,
+    count: u64,
     size: u32,
 }
 
 impl<'a> RequestBlobs<'a> {
     /// Creates a lazy blobs request (builder)
-    pub fn new(client: &'a Random) -> RequestBlobs {
+    #[must_use]
+    pub fn new(client: &'a Random) -> RequestBlobs<'a> {
         RequestBlobs {
             client,
-            limit: 10u16,
+            count: 10,
             size: 128u32,
         }
     }
 
-    builder!(limit, u16);
+    count_builder!();
     builder!(size, u32);
+
+    /// Returns the expected `bitsUsed` of the request.
+    #[must_use]
+    pub fn estimated_bits(&self) -> u64 {
+        blobs_bits(self.count, self.size)
+    }
+
+    /// Returns how many blobs of the size the service generates in a single call.
+    fn max_limit(&self) -> u16 {
+        u16::try_from(MAX_BLOBS_SIZE / self.size.max(1))
+            .unwrap_or(MAX_BLOBS_LIMIT)
+            .clamp(1, MAX_BLOBS_LIMIT)
+    }
 }
 
 /// Terminators
-impl<'a> RequestBlobs<'a> {
+impl RequestBlobs<'_> {
     /// Collect the random blobs (performs the request)
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of the calls to `Random::generate_blobs`.
     pub fn collect<T: From<Response<GenerateBlobsResult>>>(self) -> Result<T> {
-        Ok(T::from(self.client.generate_blobs(self.limit, self.size)?))
+        Ok(T::from(chunked(
+            self.client,
+            self.count,
+            self.estimated_bits(),
+            self.max_limit(),
+            |limit| self.client.generate_blobs(limit, self.size),
+        )?))
+    }
+
+    /// Returns an endless iterator over the random blobs, requesting them page by page,
+    /// `limit` blobs at a time.
+    #[must_use]
+    pub fn stream(self) -> RandomStream<String> {
+        let client = self.client.clone();
+        let size = self.size;
+        let limit = page_size(self.count, self.max_limit());
+        RandomStream::new(limit, move || {
+            Ok(client.generate_blobs(limit, size)?.result.random.data)
+        })
+    }
+}
+
+into_iter!(RequestBlobs, String);
+
+#[cfg(test)]
+mod tests {
+    use super::untaken;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_untaken_skips_taken_values() {
+        let taken = vec![1, 3, 4, 8].into_iter().collect::<BTreeSet<i64>>();
+
+        // The untaken values of [0; 9] are 0, 2, 5, 6, 7 and 9.
+        assert_eq!(
+            untaken(0, &taken, &[5, 0, 2, 1, 4, 3]),
+            vec![9, 0, 5, 2, 7, 6]
+        );
+        assert_eq!(untaken(10, &BTreeSet::new(), &[12, 10]), vec![12, 10]);
+        assert_eq!(
+            untaken(-5, &vec![-5, -4].into_iter().collect(), &[-5, -3]),
+            vec![-3, -1]
+        );
+    }
+
+    #[test]
+    fn test_gaussians_take_real_parameters() {
+        use crate::test_server::{client, random_result};
+        use crate::Error;
+
+        let random = client(|request| {
+            assert_eq!(request["params"]["mean"], 0.5);
+            assert_eq!(request["params"]["standardDeviation"], 0.01);
+            random_result(request, &serde_json::json!([0.49, 0.51]), 13)
+        });
+
+        let gaussians = random
+            .request_gaussians()
+            .limit(2)
+            .mean(0.5)
+            .standard_deviation(0.01)
+            .significant_digits(2)
+            .collect::<Vec<f64>>()
+            .unwrap();
+        assert_eq!(gaussians, vec![0.49, 0.51]);
+
+        // The integer parameters are still accepted, and checked before any request.
+        assert!(matches!(
+            random.generate_gaussians(2, 2_000_000, 1, 2),
+            Err(Error::Rest(_))
+        ));
+        assert!(matches!(
+            random.generate_gaussians(2, 0.0, f64::NAN, 2),
+            Err(Error::Rest(_))
+        ));
+    }
+
+    #[test]
+    fn test_chunked_request_checks_the_whole_budget() {
+        use crate::test_server::{client, random_result};
+        use crate::{Budget, Error};
+        use std::convert::TryFrom;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counted = Arc::clone(&calls);
+        let random = client(move |request| {
+            counted.fetch_add(1, Ordering::SeqCst);
+            let n = request["params"]["n"].as_u64().unwrap();
+            random_result(
+                request,
+                &serde_json::json!(vec![7; usize::try_from(n).unwrap()]),
+                n * 8,
+            )
+        })
+        .with_budget(Budget::new(std::time::Duration::from_secs(60)).max_bits(80_000));
+        let integers = |count| {
+            random
+                .request_integers()
+                .min(0)
+                .max(255)
+                .count(count)
+                .collect::<Vec<i32>>()
+        };
+
+        // The first 10,000 integers would fit into the budget, but not the last one.
+        assert!(matches!(integers(10_001), Err(Error::BudgetExceeded(_))));
+        assert!(matches!(integers(0), Err(Error::Rest(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+        assert_eq!(integers(10_000).unwrap().len(), 10_000);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
     }
 }
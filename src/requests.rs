@@ -1,20 +1,34 @@
 use crate::methods::Method;
-use crate::model::*;
-use crate::params::*;
+use crate::model::{AllowedCharacters, ApiKey, Request, RequestId};
+use crate::params::{
+    ApiKeyParams, GenerateBlobsParams, GenerateDecimalFractionsParams, GenerateGaussiansParams,
+    GenerateIntegersParams, GenerateStringsParams, GenerateUUIDsParams,
+};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 const API_JSON_RPC_VERSION: &str = "2.0";
 
+/// The id of the next request, shared by every client so that concurrent requests never
+/// share an id.
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Returns a request id which was not used by any other request of the process.
+fn next_request_id() -> RequestId {
+    RequestId(NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed))
+}
+
 /// Empty request - has nothing but api key inside.
 /// Used in `getUsage` method.
 pub type EmptyRequest = Request<ApiKeyParams>;
 impl EmptyRequest {
     /// Create an empty request.
+    #[must_use]
     pub fn new(method: Method, api_key: ApiKey) -> EmptyRequest {
         EmptyRequest {
             json_rpc: API_JSON_RPC_VERSION.to_owned(),
             method,
             params: ApiKeyParams { api_key },
-            id: RequestId(1),
+            id: next_request_id(),
         }
     }
 }
@@ -23,6 +37,7 @@ impl EmptyRequest {
 pub type GenerateIntegersRequest = Request<GenerateIntegersParams>;
 impl GenerateIntegersRequest {
     /// Create a request for integers generation.
+    #[must_use]
     pub fn new(
         api_key: ApiKey,
         min: i32,
@@ -40,7 +55,7 @@ impl GenerateIntegersRequest {
                 limit,
                 replacement,
             },
-            id: RequestId(1),
+            id: next_request_id(),
         }
     }
 }
@@ -49,6 +64,7 @@ impl GenerateIntegersRequest {
 pub type GenerateDecimalFractionsRequest = Request<GenerateDecimalFractionsParams>;
 impl GenerateDecimalFractionsRequest {
     /// Create a request for decimal fractions generation.
+    #[must_use]
     pub fn new(api_key: ApiKey, limit: u16, decimal_places: u8) -> GenerateDecimalFractionsRequest {
         GenerateDecimalFractionsRequest {
             json_rpc: API_JSON_RPC_VERSION.to_owned(),
@@ -58,7 +74,7 @@ impl GenerateDecimalFractionsRequest {
                 limit,
                 decimal_places,
             },
-            id: RequestId(1),
+            id: next_request_id(),
         }
     }
 }
@@ -67,11 +83,12 @@ impl GenerateDecimalFractionsRequest {
 pub type GenerateGaussiansRequest = Request<GenerateGaussiansParams>;
 impl GenerateGaussiansRequest {
     /// Create a request for gaussians generation.
+    #[must_use]
     pub fn new(
         api_key: ApiKey,
         limit: u16,
-        mean: i32,
-        standard_deviation: i32,
+        mean: f64,
+        standard_deviation: f64,
         significant_digits: u8,
     ) -> GenerateGaussiansRequest {
         GenerateGaussiansRequest {
@@ -84,7 +101,7 @@ impl GenerateGaussiansRequest {
                 standard_deviation,
                 significant_digits,
             },
-            id: RequestId(1),
+            id: next_request_id(),
         }
     }
 }
@@ -93,11 +110,12 @@ impl GenerateGaussiansRequest {
 pub type GenerateStringsRequest = Request<GenerateStringsParams>;
 impl GenerateStringsRequest {
     /// Create a request for strings generation.
+    #[must_use]
     pub fn new(
         api_key: ApiKey,
         limit: u16,
         length: u8,
-        characters: AllowedCharacters,
+        characters: &AllowedCharacters,
     ) -> GenerateStringsRequest {
         GenerateStringsRequest {
             json_rpc: API_JSON_RPC_VERSION.to_owned(),
@@ -108,7 +126,7 @@ impl GenerateStringsRequest {
                 length,
                 characters: characters.0.iter().collect::<String>(),
             },
-            id: RequestId(1),
+            id: next_request_id(),
         }
     }
 }
@@ -117,12 +135,13 @@ impl GenerateStringsRequest {
 pub type GenerateUUIDsRequest = Request<GenerateUUIDsParams>;
 impl GenerateUUIDsRequest {
     /// Create a request for strings UUIDs generation.
+    #[must_use]
     pub fn new(api_key: ApiKey, limit: u16) -> GenerateUUIDsRequest {
         GenerateUUIDsRequest {
             json_rpc: API_JSON_RPC_VERSION.to_owned(),
             method: Method::GenerateUUIDs,
             params: GenerateUUIDsParams { api_key, limit },
-            id: RequestId(1),
+            id: next_request_id(),
         }
     }
 }
@@ -131,6 +150,7 @@ impl GenerateUUIDsRequest {
 pub type GenerateBlobsRequest = Request<GenerateBlobsParams>;
 impl GenerateBlobsRequest {
     /// Create a request for blobs generation.
+    #[must_use]
     pub fn new(api_key: ApiKey, limit: u16, size: u32) -> GenerateBlobsRequest {
         GenerateBlobsRequest {
             json_rpc: API_JSON_RPC_VERSION.to_owned(),
@@ -140,7 +160,7 @@ impl GenerateBlobsRequest {
                 limit,
                 size,
             },
-            id: RequestId(1),
+            id: next_request_id(),
         }
     }
 }
@@ -1,17 +1,16 @@
 use crate::date_de;
-use crate::model::{ApiKeyStatus, Response};
-use chrono;
+use crate::model::{ApiKeyStatus, ExactDecimal, Response};
 
 /// A random.org response of `getUsage` method.
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct GetUsageResult {
     /// A string indicating the API key's current status, which may be stopped, paused or running.
     /// An API key must be running for it to be able to serve requests.
     pub status: ApiKeyStatus,
     /// A time at which the API key was created.
     #[serde(rename = "creationTime")]
-    #[serde(deserialize_with = "date_de::deserialize")]
-    pub creation_time: chrono::NaiveDateTime,
+    #[serde(with = "date_de")]
+    pub creation_time: chrono::DateTime<chrono::Utc>,
     /// An integer containing the (estimated) number of remaining true random bits available to
     /// the client.
     #[serde(rename = "bitsLeft")]
@@ -29,18 +28,18 @@ pub struct GetUsageResult {
 }
 
 /// A random.org's produced random data (from `generateIntegers` method)
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct RandomData<T> {
     /// An array containing the sequence of numbers requested.
     pub data: Vec<T>,
     /// A time at which the request was completed.
     #[serde(rename = "completionTime")]
-    #[serde(deserialize_with = "date_de::deserialize")]
-    pub completion_time: chrono::NaiveDateTime,
+    #[serde(with = "date_de")]
+    pub completion_time: chrono::DateTime<chrono::Utc>,
 }
 
 /// A random.org response with random data.
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct RandomResult<RandomDataType> {
     /// This object encapsulates the random numbers and associated data. It contains the following
     /// properties.
@@ -66,10 +65,17 @@ pub struct RandomResult<RandomDataType> {
 pub type GenerateIntegersResult = RandomResult<i32>;
 
 /// A random.org response of `GenerateDecimalFractions` method.
-pub type GenerateDecimalFractionsResult = RandomResult<f32>;
+pub type GenerateDecimalFractionsResult = RandomResult<f64>;
+
+/// A random.org response of `GenerateDecimalFractions` method, keeping every digit of the
+/// decimal fractions.
+pub type GenerateDecimalFractionsExactResult = RandomResult<ExactDecimal>;
 
 /// A random.org response of `GenerateGaussians` method.
-pub type GenerateGaussiansResult = RandomResult<f32>;
+pub type GenerateGaussiansResult = RandomResult<f64>;
+
+/// A random.org response of `GenerateGaussians` method, keeping every digit of the gaussians.
+pub type GenerateGaussiansExactResult = RandomResult<ExactDecimal>;
 
 /// A random.org response of `GenerateStrings` method.
 pub type GenerateStringsResult = RandomResult<String>;
@@ -80,14 +86,61 @@ pub type GenerateUUIDsResult = RandomResult<String>;
 /// A random.org response of `GenerateBlobs` method.
 pub type GenerateBlobsResult = RandomResult<String>;
 
+impl<T> Response<RandomResult<T>> {
+    /// Appends the random data of a later response to this one, keeping the latest quota
+    /// and completion time.
+    pub(crate) fn append(&mut self, later: Response<RandomResult<T>>) {
+        self.json_rpc = later.json_rpc;
+        self.id = later.id;
+        self.result.random.data.extend(later.result.random.data);
+        self.result.random.completion_time = later.result.random.completion_time;
+        self.result.bits_used += later.result.bits_used;
+        self.result.bits_left = later.result.bits_left;
+        self.result.requests_left = later.result.requests_left;
+        self.result.advisory_delay = later.result.advisory_delay;
+    }
+}
+
 impl From<Response<RandomResult<i32>>> for Vec<i32> {
     fn from(response: Response<RandomResult<i32>>) -> Vec<i32> {
         response.result.random.data
     }
 }
 
-impl From<Response<RandomResult<f32>>> for Vec<f32> {
-    fn from(response: Response<RandomResult<f32>>) -> Vec<f32> {
+impl From<Response<RandomResult<f64>>> for Vec<f64> {
+    fn from(response: Response<RandomResult<f64>>) -> Vec<f64> {
+        response.result.random.data
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl std::convert::TryFrom<Response<RandomResult<String>>> for Vec<uuid::Uuid> {
+    type Error = crate::Error;
+
+    /// Parses the UUIDs, checking that every one of them is a version 4 RFC 4122 UUID.
+    fn try_from(response: Response<RandomResult<String>>) -> crate::Result<Vec<uuid::Uuid>> {
+        response
+            .result
+            .random
+            .data
+            .iter()
+            .map(|uuid| {
+                uuid::Uuid::parse_str(uuid)
+                    .ok()
+                    .filter(|uuid| {
+                        uuid.get_version() == Some(uuid::Version::Random)
+                            && uuid.get_variant() == uuid::Variant::RFC4122
+                    })
+                    .ok_or(crate::Error::Rest(
+                        "The service returned a malformed version 4 UUID.",
+                    ))
+            })
+            .collect()
+    }
+}
+
+impl From<Response<RandomResult<ExactDecimal>>> for Vec<ExactDecimal> {
+    fn from(response: Response<RandomResult<ExactDecimal>>) -> Vec<ExactDecimal> {
         response.result.random.data
     }
 }
@@ -99,9 +152,8 @@ impl From<Response<RandomResult<String>>> for Vec<String> {
 }
 
 #[cfg(test)]
+#[allow(clippy::excessive_precision, clippy::unreadable_literal)]
 mod parse_tests {
-    use serde_json;
-
     #[test]
     fn test_get_usage_response_parse() {
         use crate::{ApiKeyStatus, GetUsageResult, RequestId, Response};
@@ -128,7 +180,7 @@ mod parse_tests {
         assert_eq!(u.id, RequestId(1));
         assert_eq!(u.result.status, ApiKeyStatus::Running);
         assert_eq!(u.result.creation_time.year(), 2017i32);
-        assert_eq!(u.result.creation_time.month(), 06u32);
+        assert_eq!(u.result.creation_time.month(), 6u32);
         assert_eq!(u.result.creation_time.day(), 22u32);
         assert_eq!(u.result.creation_time.hour(), 13u32);
         assert_eq!(u.result.creation_time.minute(), 32u32);
@@ -178,6 +230,32 @@ mod parse_tests {
         assert_eq!(u.result.bits_left, 199984);
         assert_eq!(u.result.requests_left, 9999);
         assert_eq!(u.result.advisory_delay, 0);
+        assert_eq!(
+            crate::Random::new("")
+                .request_integers()
+                .min(1)
+                .max(6)
+                .limit(6)
+                .estimated_bits(),
+            u.result.bits_used
+        );
+
+        // Unique integers are picked among the 6 * 5 * 4 ordered selections, and no more
+        // integers than the range contains may be picked.
+        let random = crate::Random::new("");
+        let unique = |min, max, count| {
+            random
+                .request_integers()
+                .min(min)
+                .max(max)
+                .count(count)
+                .replacement(false)
+                .estimated_bits()
+        };
+        assert_eq!(unique(1, 6, 3), 7);
+        assert_eq!(unique(1, 6, u64::MAX), 9);
+        // log2(1e9!) = 28,454,657,829.37...
+        assert_eq!(unique(1, 1_000_000_000, u64::MAX), 28_454_657_829);
     }
 
     #[test]
@@ -196,8 +274,8 @@ mod parse_tests {
                     ],
                     "completionTime": "2011-10-10 13:19:12Z"
                 },
-                "bitsUsed": 16,
-                "bitsLeft": 199984,
+                "bitsUsed": 266,
+                "bitsLeft": 199734,
                 "requestsLeft": 9999,
                 "advisoryDelay": 0
             },
@@ -222,10 +300,60 @@ mod parse_tests {
         assert_eq!(u.result.random.completion_time.hour(), 13u32);
         assert_eq!(u.result.random.completion_time.minute(), 19u32);
         assert_eq!(u.result.random.completion_time.second(), 12u32);
-        assert_eq!(u.result.bits_used, 16);
-        assert_eq!(u.result.bits_left, 199984);
+        assert_eq!(u.result.bits_used, 266);
+        assert_eq!(u.result.bits_left, 199734);
         assert_eq!(u.result.requests_left, 9999);
         assert_eq!(u.result.advisory_delay, 0);
+        assert_eq!(
+            crate::Random::new("")
+                .request_decimal_fractions()
+                .limit(10)
+                .decimal_places(8)
+                .estimated_bits(),
+            u.result.bits_used
+        );
+    }
+
+    #[test]
+    fn test_exact_decimals_keep_every_digit() {
+        use crate::{ExactDecimal, GenerateGaussiansExactResult, Response};
+
+        let s = r#"
+        {
+            "jsonrpc": "2.0",
+            "result": {
+                "random": {
+                    "data": [0.12345678901234567891, -1.2e-7],
+                    "completionTime": "2011-10-10 13:19:12Z"
+                },
+                "bitsUsed": 133,
+                "bitsLeft": 199867,
+                "requestsLeft": 9999,
+                "advisoryDelay": 0
+            },
+            "id": 42
+        }
+        "#;
+
+        let u: Response<GenerateGaussiansExactResult> = serde_json::from_str(s).unwrap();
+        let data: Vec<ExactDecimal> = u.into();
+
+        assert_eq!(data[0].as_str(), "0.12345678901234567891");
+        assert!((data[0].to_f64() - 0.123_456_789_012_345_68).abs() < f64::EPSILON);
+        assert_eq!(data[1].to_string(), "-1.2e-7");
+        #[cfg(feature = "decimal")]
+        {
+            use std::str::FromStr;
+            assert_eq!(
+                data[0].to_decimal().unwrap(),
+                rust_decimal::Decimal::from_str("0.12345678901234567891").unwrap()
+            );
+            assert_eq!(
+                data[1].to_decimal().unwrap(),
+                rust_decimal::Decimal::from_str("-0.00000012").unwrap()
+            );
+        }
+        assert!(serde_json::from_str::<ExactDecimal>("\"0.5\"").is_err());
     }
 
     #[test]
@@ -243,8 +371,8 @@ mod parse_tests {
                     ],
                     "completionTime": "2011-10-10 13:19:12Z"
                 },
-                "bitsUsed": 16,
-                "bitsLeft": 199984,
+                "bitsUsed": 106,
+                "bitsLeft": 199894,
                 "requestsLeft": 9999,
                 "advisoryDelay": 0
             },
@@ -266,10 +394,18 @@ mod parse_tests {
         assert_eq!(u.result.random.completion_time.hour(), 13u32);
         assert_eq!(u.result.random.completion_time.minute(), 19u32);
         assert_eq!(u.result.random.completion_time.second(), 12u32);
-        assert_eq!(u.result.bits_used, 16);
-        assert_eq!(u.result.bits_left, 199984);
+        assert_eq!(u.result.bits_used, 106);
+        assert_eq!(u.result.bits_left, 199894);
         assert_eq!(u.result.requests_left, 9999);
         assert_eq!(u.result.advisory_delay, 0);
+        assert_eq!(
+            crate::Random::new("")
+                .request_gaussians()
+                .limit(4)
+                .significant_digits(8)
+                .estimated_bits(),
+            u.result.bits_used
+        );
     }
 
     #[test]
@@ -288,8 +424,8 @@ mod parse_tests {
                     ],
                     "completionTime": "2011-10-10 13:19:12Z"
                 },
-                "bitsUsed": 16,
-                "bitsLeft": 199984,
+                "bitsUsed": 376,
+                "bitsLeft": 199624,
                 "requestsLeft": 9999,
                 "advisoryDelay": 0
             },
@@ -320,10 +456,19 @@ mod parse_tests {
         assert_eq!(u.result.random.completion_time.hour(), 13u32);
         assert_eq!(u.result.random.completion_time.minute(), 19u32);
         assert_eq!(u.result.random.completion_time.second(), 12u32);
-        assert_eq!(u.result.bits_used, 16);
-        assert_eq!(u.result.bits_left, 199984);
+        assert_eq!(u.result.bits_used, 376);
+        assert_eq!(u.result.bits_left, 199624);
         assert_eq!(u.result.requests_left, 9999);
         assert_eq!(u.result.advisory_delay, 0);
+        assert_eq!(
+            crate::Random::new("")
+                .request_strings()
+                .limit(8)
+                .length(10)
+                .characters(crate::AllowedCharacters(('a'..='z').collect()))
+                .estimated_bits(),
+            u.result.bits_used
+        );
     }
 
     #[test]
@@ -341,8 +486,8 @@ mod parse_tests {
                     ],
                     "completionTime": "2011-10-10 13:19:12Z"
                 },
-                "bitsUsed": 16,
-                "bitsLeft": 199984,
+                "bitsUsed": 122,
+                "bitsLeft": 199878,
                 "requestsLeft": 9999,
                 "advisoryDelay": 0
             },
@@ -364,10 +509,50 @@ mod parse_tests {
         assert_eq!(u.result.random.completion_time.hour(), 13u32);
         assert_eq!(u.result.random.completion_time.minute(), 19u32);
         assert_eq!(u.result.random.completion_time.second(), 12u32);
-        assert_eq!(u.result.bits_used, 16);
-        assert_eq!(u.result.bits_left, 199984);
+        assert_eq!(u.result.bits_used, 122);
+        assert_eq!(u.result.bits_left, 199878);
         assert_eq!(u.result.requests_left, 9999);
         assert_eq!(u.result.advisory_delay, 0);
+        assert_eq!(
+            crate::Random::new("")
+                .request_uuids()
+                .limit(1)
+                .estimated_bits(),
+            u.result.bits_used
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "uuid")]
+    fn test_collect_typed_uuids() {
+        use crate::test_server::{client, random_result};
+        use crate::Error;
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let malformed = AtomicBool::new(false);
+        let random = client(move |request| {
+            // The second UUID is a version 1 one.
+            let uuids = if malformed.swap(true, Ordering::SeqCst) {
+                vec![
+                    "47849fd4-b790-492e-8b93-c601a91b662d",
+                    "c232ab00-9414-11ec-b3c8-9e6bdeced846",
+                ]
+            } else {
+                vec!["47849fd4-b790-492e-8b93-c601a91b662d"]
+            };
+            random_result(request, &serde_json::json!(uuids), 122)
+        });
+
+        let uuids = random
+            .request_uuids()
+            .limit(1)
+            .collect::<Vec<uuid::Uuid>>()
+            .unwrap();
+        assert_eq!(uuids[0].to_string(), "47849fd4-b790-492e-8b93-c601a91b662d");
+        assert!(matches!(
+            random.request_uuids().limit(2).collect::<Vec<uuid::Uuid>>(),
+            Err(Error::Rest(_))
+        ));
     }
 
     #[test]
@@ -386,8 +571,8 @@ mod parse_tests {
                     ],
                     "completionTime": "2011-10-10 13:19:12Z"
                 },
-                "bitsUsed": 16,
-                "bitsLeft": 199984,
+                "bitsUsed": 1024,
+                "bitsLeft": 198976,
                 "requestsLeft": 9999,
                 "advisoryDelay": 0
             },
@@ -406,10 +591,18 @@ mod parse_tests {
         assert_eq!(u.result.random.completion_time.hour(), 13u32);
         assert_eq!(u.result.random.completion_time.minute(), 19u32);
         assert_eq!(u.result.random.completion_time.second(), 12u32);
-        assert_eq!(u.result.bits_used, 16);
-        assert_eq!(u.result.bits_left, 199984);
+        assert_eq!(u.result.bits_used, 1024);
+        assert_eq!(u.result.bits_left, 198976);
         assert_eq!(u.result.requests_left, 9999);
         assert_eq!(u.result.advisory_delay, 0);
+        assert_eq!(
+            crate::Random::new("")
+                .request_blobs()
+                .limit(1)
+                .size(1024)
+                .estimated_bits(),
+            u.result.bits_used
+        );
     }
 
     #[test]
@@ -446,7 +639,7 @@ mod parse_tests {
                         "aNB8L3hY3kWYXgTUQxGVB5njMe2e0l3LCjkDCN1u12kPBPrsDcWMLTCDlB60kRhAlGbvPqoBHhjg6ZbOM4LfD3T9/wfhvnqJ1FTraamW2IAUnyKxz27fgcPw1So6ToIBL0fGQLpMQDF2/nEmNmFRNa9s6sQ+400IGA+ZeaOAgjE="
             
                     ],
-                    "completionTime": "2011-10-10 13:19:12Z"
+                    "completionTime": "2011-10-10T13:19:12.000Z"
                 },
                 "bitsUsed": 16,
                 "bitsLeft": 199984,
@@ -457,7 +650,7 @@ mod parse_tests {
         }
         "#;
 
-        for s in vec![correct, incorrect] {
+        for s in [correct, incorrect] {
             let u: Response<GenerateBlobsResult> = serde_json::from_str(s).unwrap();
 
             assert_eq!(u.json_rpc, "2.0");
@@ -475,4 +668,71 @@ mod parse_tests {
             assert_eq!(u.result.advisory_delay, 0);
         }
     }
+
+    #[test]
+    fn test_responses_round_trip() {
+        use crate::{
+            GenerateGaussiansExactResult, GenerateStringsResult, GetUsageResult, Response,
+        };
+        use serde_json::Value;
+
+        fn round_trip<R: serde::Serialize + serde::de::DeserializeOwned>(s: &str) {
+            let original: Value = serde_json::from_str(s).unwrap();
+            let response: Response<R> = serde_json::from_str(s).unwrap();
+            let serialized = serde_json::to_string(&response).unwrap();
+            assert_eq!(
+                serde_json::from_str::<Value>(&serialized).unwrap(),
+                original
+            );
+            let again: Response<R> = serde_json::from_str(&serialized).unwrap();
+            assert_eq!(serde_json::to_string(&again).unwrap(), serialized);
+        }
+
+        round_trip::<GetUsageResult>(
+            r#"{
+                "jsonrpc": "2.0",
+                "result": {
+                    "status": "running",
+                    "creationTime": "2017-06-22 13:32:16Z",
+                    "bitsLeft": 250000,
+                    "requestsLeft": 1000,
+                    "totalBits": 0,
+                    "totalRequests": 0
+                },
+                "id": 1
+            }"#,
+        );
+        round_trip::<GenerateStringsResult>(
+            r#"{
+                "jsonrpc": "2.0",
+                "result": {
+                    "random": {
+                        "data": ["grvhglvahj", "hjrmosnvla"],
+                        "completionTime": "2011-10-10 13:19:12.250Z"
+                    },
+                    "bitsUsed": 94,
+                    "bitsLeft": 199906,
+                    "requestsLeft": 9999,
+                    "advisoryDelay": 0
+                },
+                "id": 42
+            }"#,
+        );
+        round_trip::<GenerateGaussiansExactResult>(
+            r#"{
+                "jsonrpc": "2.0",
+                "result": {
+                    "random": {
+                        "data": [0.12345678901234567891, -1.2e-7],
+                        "completionTime": "2011-10-10 13:19:12Z"
+                    },
+                    "bitsUsed": 133,
+                    "bitsLeft": 199867,
+                    "requestsLeft": 9999,
+                    "advisoryDelay": 0
+                },
+                "id": 42
+            }"#,
+        );
+    }
 }
@@ -0,0 +1,385 @@
+//! Shuffling and sampling on top of the unique integers generated by the service.
+
+use crate::{Error, GenerateIntegersResult, Random, Result};
+use std::convert::TryFrom;
+
+/// The largest collection whose indices are drawn at once: the indices must fit the
+/// [-1e9, 1e9] range of the integers the service generates.
+const MAX_DRAWN_LEN: usize = 1_000_000_001;
+
+/// The base of the digits combined into the indices of the collections too large for a single
+/// draw.
+const DIGIT_BASE: u128 = 1_000_000_000;
+
+/// A random permutation of indices drawn from the service.
+#[derive(Debug, Clone)]
+pub struct Permutation {
+    /// The permuted indices: the element at the position `i` of a shuffled slice comes from
+    /// the position `indices[i]` of the original one.
+    pub indices: Vec<usize>,
+    /// The results of the calls the indices were drawn from, as the service returned them, for
+    /// auditing. Every call after the first one draws the positions of its indices among the
+    /// ones which were not drawn yet. It is empty when there were less than two indices to
+    /// permute and no call was made.
+    pub results: Vec<GenerateIntegersResult>,
+}
+
+impl Permutation {
+    /// Moves the elements of `items` to the positions of the permutation.
+    fn apply<T>(&self, items: &mut [T]) {
+        let mut placed = vec![false; self.indices.len()];
+        for start in 0..self.indices.len() {
+            let mut position = start;
+            while !placed[position] {
+                placed[position] = true;
+                let source = self.indices[position];
+                if source == start {
+                    break;
+                }
+                items.swap(position, source);
+                position = source;
+            }
+        }
+    }
+}
+
+/// Returns `true` if the `indices` of the `0..len` range are all different, marking the ones
+/// seen already in a bitmap.
+fn are_unique(indices: &[usize], len: usize) -> bool {
+    let mut seen = vec![false; len];
+    indices
+        .iter()
+        .all(|&i| i < len && !std::mem::replace(&mut seen[i], true))
+}
+
+impl Random {
+    /// Returns a random permutation of the `0..len` indices.
+    ///
+    /// The indices are drawn as unique integers with `generate_integers`, so every
+    /// permutation is equally likely. Permutations longer than 10,000 indices take several
+    /// calls, each one picking among the indices which have not been drawn yet.
+    ///
+    /// # Usage
+    ///
+    /// ```rust,no_run
+    /// extern crate randomorg;
+    ///
+    /// fn main() {
+    ///     use randomorg::Random;
+    ///     let r = Random::new("API KEY HERE");
+    ///     let permutation = r.permutation(52).unwrap();
+    ///     println!("Indices: {:?}", permutation.indices);
+    ///     println!("Drawn: {:?}", permutation.results);
+    /// }
+    /// ```
+    ///
+    /// # Constraints
+    /// * `len` must be within [0; 1e9 + 1] range
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Rest` if `len` is too large or the service returns something else than a
+    /// permutation, and the error of the service if a call fails.
+    pub fn permutation(&self, len: usize) -> Result<Permutation> {
+        if len < 2 {
+            return Ok(Permutation {
+                indices: (0..len).collect(),
+                results: Vec::new(),
+            });
+        }
+        let max = i32::try_from(len - 1)
+            .ok()
+            .filter(|_| len <= MAX_DRAWN_LEN)
+            .ok_or(Error::Rest(
+                "The service can't permute more than 1,000,000,001 elements.",
+            ))?;
+        let (drawn, responses) = self
+            .request_integers()
+            .min(0)
+            .max(max)
+            .count(len as u64)
+            .replacement(false)
+            .collect_unique_calls()?;
+        let indices = drawn
+            .iter()
+            .map(|&i| usize::try_from(i))
+            .collect::<std::result::Result<Vec<usize>, _>>()
+            .ok()
+            .filter(|indices| indices.len() == len && are_unique(indices, len))
+            .ok_or(Error::Rest("The service returned a wrong permutation."))?;
+        Ok(Permutation {
+            indices,
+            results: responses.into_iter().map(|r| r.result).collect(),
+        })
+    }
+
+    /// Shuffles `items` in place with a permutation drawn by `permutation`, which is returned
+    /// for auditing.
+    ///
+    /// # Usage
+    ///
+    /// ```rust,no_run
+    /// extern crate randomorg;
+    ///
+    /// fn main() {
+    ///     use randomorg::Random;
+    ///     let r = Random::new("API KEY HERE");
+    ///     let mut players = vec!["Alice", "Bob", "Carol"];
+    ///     r.shuffle(&mut players).unwrap();
+    ///     println!("Order: {:?}", players);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `Random::permutation`, leaving the items untouched.
+    pub fn shuffle<T>(&self, items: &mut [T]) -> Result<Permutation> {
+        let permutation = self.permutation(items.len())?;
+        permutation.apply(items);
+        Ok(permutation)
+    }
+
+    /// Picks `k` different items of `items` at random, in the order they were drawn.
+    ///
+    /// The indices of the items are drawn as unique integers with `generate_integers`. The
+    /// indices of collections larger than the range of the integers generated by the service
+    /// are combined out of several draws, the ones out of the collection or picked already being
+    /// drawn again.
+    ///
+    /// # Usage
+    ///
+    /// ```rust,no_run
+    /// extern crate randomorg;
+    ///
+    /// fn main() {
+    ///     use randomorg::Random;
+    ///     let r = Random::new("API KEY HERE");
+    ///     let tickets = (1..=500).collect::<Vec<u32>>();
+    ///     println!("Winners: {:?}", r.sample(&tickets, 3));
+    /// }
+    /// ```
+    ///
+    /// # Constraints
+    /// * `k` must not be greater than the number of items
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Rest` if `k` is greater than the number of items, and the error of the
+    /// service if a call fails.
+    pub fn sample<'a, T>(&self, items: &'a [T], k: usize) -> Result<Vec<&'a T>> {
+        Ok(self
+            .sample_indices(items.len(), k)?
+            .into_iter()
+            .map(|i| &items[i])
+            .collect())
+    }
+
+    /// Picks an item of `items` at random, like `sample` picking a single one.
+    ///
+    /// # Usage
+    ///
+    /// ```rust,no_run
+    /// extern crate randomorg;
+    ///
+    /// fn main() {
+    ///     use randomorg::Random;
+    ///     let r = Random::new("API KEY HERE");
+    ///     println!("Winner: {:?}", r.choose(&["Alice", "Bob", "Carol"]));
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Rest` if there is no item, and the error of the service if the call fails.
+    pub fn choose<'a, T>(&self, items: &'a [T]) -> Result<&'a T> {
+        if items.is_empty() {
+            return Err(Error::Rest("Can't choose an item of an empty collection."));
+        }
+        Ok(self.sample(items, 1)?[0])
+    }
+
+    /// Draws `k` different indices of the `0..len` range.
+    fn sample_indices(&self, len: usize, k: usize) -> Result<Vec<usize>> {
+        if k > len {
+            return Err(Error::Rest(
+                "Can't sample more items than the collection contains.",
+            ));
+        }
+        if k == 0 {
+            return Ok(Vec::new());
+        }
+        if len > MAX_DRAWN_LEN {
+            return self.sample_combined_indices(len, k);
+        }
+        let max = i32::try_from(len - 1).unwrap_or(i32::MAX);
+        let indices = self
+            .request_integers()
+            .min(0)
+            .max(max)
+            .count(k as u64)
+            .replacement(false)
+            .collect::<Vec<i32>>()?
+            .into_iter()
+            .map(|i| {
+                usize::try_from(i)
+                    .ok()
+                    .filter(|&i| i < len)
+                    .ok_or(Error::Rest(
+                        "The service returned an integer out of the range.",
+                    ))
+            })
+            .collect::<Result<Vec<usize>>>()?;
+        if indices.len() != k || !are_unique(&indices, len) {
+            return Err(Error::Rest("The service returned repeated integers."));
+        }
+        Ok(indices)
+    }
+
+    /// Draws `k` different indices of a range too large for a single draw: every index is made
+    /// of several digits in base 1e9, each one drawn separately, and the indices out of the
+    /// range or drawn already are drawn again.
+    fn sample_combined_indices(&self, len: usize, k: usize) -> Result<Vec<usize>> {
+        let len = len as u128;
+        let mut scale = 1;
+        while scale * DIGIT_BASE < len {
+            scale *= DIGIT_BASE;
+        }
+        let top_max = i32::try_from(len.div_ceil(scale) - 1).unwrap_or(i32::MAX);
+        let digit_max = i32::try_from(DIGIT_BASE - 1).unwrap_or(i32::MAX);
+        let draw = |max: i32, count: usize| -> Result<Vec<u128>> {
+            self.request_integers()
+                .min(0)
+                .max(max)
+                .count(count as u64)
+                .replacement(true)
+                .collect::<Vec<i32>>()?
+                .into_iter()
+                .map(|digit| {
+                    u128::try_from(digit).map_err(|_| {
+                        Error::Rest("The service returned an integer out of the range.")
+                    })
+                })
+                .collect()
+        };
+        let mut picked = std::collections::BTreeSet::new();
+        let mut indices = Vec::with_capacity(k);
+        while indices.len() < k {
+            let count = k - indices.len();
+            let mut candidates = draw(top_max, count)?
+                .into_iter()
+                .map(|digit| digit * scale)
+                .collect::<Vec<u128>>();
+            let mut digit_scale = scale / DIGIT_BASE;
+            while digit_scale > 0 {
+                for (candidate, digit) in candidates.iter_mut().zip(draw(digit_max, count)?) {
+                    *candidate += digit * digit_scale;
+                }
+                digit_scale /= DIGIT_BASE;
+            }
+            for candidate in candidates {
+                if candidate < len && picked.insert(candidate) {
+                    indices.push(usize::try_from(candidate).unwrap_or(usize::MAX));
+                }
+            }
+        }
+        Ok(indices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_server::{client, random_result};
+    use crate::Random;
+    use std::convert::TryFrom;
+
+    fn unique_integers_server() -> Random {
+        client(|request| {
+            let params = &request["params"];
+            assert_eq!(params["replacement"], false);
+            let (min, max) = (
+                params["min"].as_i64().unwrap(),
+                params["max"].as_i64().unwrap(),
+            );
+            let n = usize::try_from(params["n"].as_u64().unwrap()).unwrap();
+            // Rotates small ranges and reverses large ones.
+            let data = if max - min < 5 {
+                (min + 1..=max)
+                    .chain(std::iter::once(min))
+                    .collect::<Vec<_>>()
+            } else {
+                (min..=max).rev().take(n).collect()
+            };
+            random_result(request, &serde_json::json!(data), 10)
+        })
+    }
+
+    #[test]
+    fn test_permutation_spans_several_calls() {
+        let random = unique_integers_server();
+        let permutation = random.permutation(25_000).unwrap();
+        let mut sorted = permutation.indices.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..25_000).collect::<Vec<usize>>());
+        assert_eq!(permutation.indices[..2], [24_999, 24_998]);
+        // The results are the ones of the calls: the last two calls draw the positions of their
+        // indices among the 15,000 and 5,000 ones which were not drawn yet.
+        let results = &permutation.results;
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[1].random.data[0], 14_999);
+        assert_eq!(results[2].random.data[..2], [4_999, 4_998]);
+        assert!(results.iter().all(|result| result.bits_used == 10));
+        assert!(random.permutation(1).unwrap().results.is_empty());
+    }
+
+    #[test]
+    fn test_permutation_rejects_repeated_indices() {
+        let random = client(|request| random_result(request, &serde_json::json!([2, 0, 2]), 10));
+        assert!(random.permutation(3).is_err());
+        assert!(random.sample(&['a', 'b', 'c'], 3).is_err());
+    }
+
+    #[test]
+    fn test_sample_picks_different_items() {
+        let random = unique_integers_server();
+        let items = (0..10).map(|i| i * 10).collect::<Vec<u32>>();
+        assert_eq!(random.sample(&items, 3).unwrap(), vec![&90, &80, &70]);
+        assert_eq!(random.choose(&items).unwrap(), &90);
+        assert!(random.sample(&items, 11).is_err());
+        assert!(random.sample(&items, 0).unwrap().is_empty());
+        assert!(random.choose::<u32>(&[]).is_err());
+    }
+
+    #[test]
+    fn test_sample_combines_draws_for_large_collections() {
+        let drawn = std::sync::Arc::new(std::sync::atomic::AtomicI64::new(0));
+        let counter = std::sync::Arc::clone(&drawn);
+        let random = client(move |request| {
+            let params = &request["params"];
+            assert_eq!(params["replacement"], true);
+            let (min, max) = (
+                params["min"].as_i64().unwrap(),
+                params["max"].as_i64().unwrap(),
+            );
+            let n = params["n"].as_i64().unwrap();
+            let first = counter.fetch_add(n, std::sync::atomic::Ordering::SeqCst);
+            let data = (first..first + n)
+                .map(|i| min + i % (max - min + 1))
+                .collect::<Vec<i64>>();
+            random_result(request, &serde_json::json!(data), 10)
+        });
+        // The top digits 0, 1, 2 and the low digits 3, 4, 5 make 2_000_000_005, which is out of
+        // the range and drawn again as 7.
+        let indices = random.sample_indices(2_000_000_002, 3).unwrap();
+        assert_eq!(indices, vec![3, 1_000_000_004, 7]);
+        assert_eq!(drawn.load(std::sync::atomic::Ordering::SeqCst), 8);
+    }
+
+    #[test]
+    fn test_shuffle_applies_the_permutation() {
+        let random = unique_integers_server();
+        let mut items = vec!['a', 'b', 'c', 'd', 'e'];
+        let permutation = random.shuffle(&mut items).unwrap();
+        assert_eq!(permutation.indices, vec![1, 2, 3, 4, 0]);
+        assert_eq!(items, vec!['b', 'c', 'd', 'e', 'a']);
+    }
+}
@@ -0,0 +1,174 @@
+//! Lazy iterators over random values, fetching them from random.org page by page.
+//!
+//! # Usage
+//!
+//! ```rust,no_run
+//! use randomorg::Random;
+//!
+//! let r = Random::new("API KEY HERE");
+//! let dice = r
+//!     .request_integers()
+//!     .min(1)
+//!     .max(6)
+//!     .limit(100)
+//!     .stream()
+//!     .take(250)
+//!     .collect::<randomorg::Result<Vec<i32>>>();
+//! println!("Dice rolls: {:?}", dice);
+//! ```
+
+use crate::{Error, Result};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+type Fetch<T> = Arc<dyn Fn() -> Result<Vec<T>> + Send + Sync>;
+
+/// An endless iterator over random values, which requests a new page of values from the
+/// service whenever the current one runs out.
+///
+/// Once the values of the first page are used up, the consumer needs more than a page, so
+/// from then on the next page is prefetched in the background whenever only a quarter of the
+/// current page is left, and the values keep coming without waiting for the service. A page
+/// being prefetched when the stream is dropped is waited for, unless its request was not sent
+/// yet. The first error ends the iteration: it is returned as the last item.
+pub struct RandomStream<T> {
+    fetch: Fetch<T>,
+    buffer: VecDeque<T>,
+    prefetch_below: usize,
+    fetched_pages: usize,
+    pending: Option<JoinHandle<Result<Vec<T>>>>,
+    cancelled: Arc<AtomicBool>,
+    done: bool,
+}
+
+impl<T: Send + 'static> RandomStream<T> {
+    /// Creates a stream over the pages of `page_size` values returned by `fetch`.
+    pub(crate) fn new<F>(page_size: u16, fetch: F) -> RandomStream<T>
+    where
+        F: Fn() -> Result<Vec<T>> + Send + Sync + 'static,
+    {
+        RandomStream {
+            fetch: Arc::new(fetch),
+            buffer: VecDeque::new(),
+            prefetch_below: usize::from(page_size / 4),
+            fetched_pages: 0,
+            pending: None,
+            cancelled: Arc::new(AtomicBool::new(false)),
+            done: false,
+        }
+    }
+
+    fn prefetch(&mut self) {
+        if self.pending.is_none() {
+            let fetch = Arc::clone(&self.fetch);
+            let cancelled = Arc::clone(&self.cancelled);
+            self.pending = Some(std::thread::spawn(move || {
+                if cancelled.load(Ordering::SeqCst) {
+                    return Ok(Vec::new());
+                }
+                fetch()
+            }));
+        }
+    }
+}
+
+impl<T: Send + 'static> Iterator for RandomStream<T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        if self.done {
+            return None;
+        }
+        if self.buffer.is_empty() {
+            let page = match self.pending.take() {
+                Some(pending) => pending.join().unwrap_or(Err(Error::Rest(
+                    "The prefetching of random values panicked.",
+                ))),
+                None => (self.fetch)(),
+            };
+            self.fetched_pages = self.fetched_pages.saturating_add(1);
+            match page {
+                Ok(page) if page.is_empty() => {
+                    self.done = true;
+                    return Some(Err(Error::Rest(
+                        "The service returned an empty page of random values.",
+                    )));
+                }
+                Ok(page) => self.buffer.extend(page),
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+        let value = self.buffer.pop_front()?;
+        // The first page being used up, the consumer needs more than a page.
+        if self.fetched_pages > 1 && self.buffer.len() <= self.prefetch_below {
+            self.prefetch();
+        }
+        Some(Ok(value))
+    }
+}
+
+impl<T> Drop for RandomStream<T> {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        if let Some(pending) = self.pending.take() {
+            let _ = pending.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RandomStream;
+    use crate::Error;
+    use std::sync::atomic::{AtomicI32, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_stream_fetches_pages_on_demand() {
+        let pages = Arc::new(AtomicI32::new(0));
+        let fetched = Arc::clone(&pages);
+        let stream = RandomStream::new(4, move || {
+            let page = fetched.fetch_add(1, Ordering::SeqCst);
+            Ok((0..4).map(|i| page * 4 + i).collect())
+        });
+
+        let values = stream
+            .take(10)
+            .collect::<crate::Result<Vec<i32>>>()
+            .unwrap();
+
+        assert_eq!(values, (0..10).collect::<Vec<i32>>());
+        // The third page is requested before its values are taken.
+        assert_eq!(pages.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_stream_does_not_prefetch_within_first_page() {
+        let pages = Arc::new(AtomicI32::new(0));
+        let fetched = Arc::clone(&pages);
+        let stream = RandomStream::new(4, move || {
+            fetched.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![1, 2, 3, 4])
+        });
+
+        assert_eq!(stream.take(4).count(), 4);
+        assert_eq!(pages.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_stream_ends_with_error() {
+        let mut stream = RandomStream::<i32>::new(10, || Err(Error::Rest("Failed.")));
+
+        assert!(matches!(stream.next(), Some(Err(Error::Rest("Failed.")))));
+        assert!(stream.next().is_none());
+
+        let mut stream = RandomStream::<i32>::new(10, || Ok(Vec::new()));
+        assert!(matches!(stream.next(), Some(Err(Error::Rest(_)))));
+        assert!(stream.next().is_none());
+    }
+}
@@ -0,0 +1,117 @@
+//! A fake random.org service for the tests, answering the JSON-RPC calls on a local port.
+
+use crate::Random;
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+/// Starts a fake service which answers every call with the response built by `handler` from
+/// the JSON-RPC request, and returns its endpoint.
+pub(crate) fn serve<F>(handler: F) -> String
+where
+    F: Fn(&Value) -> Value + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind the fake service.");
+    let endpoint = format!("http://{}/", listener.local_addr().unwrap());
+    let handler = std::sync::Arc::new(handler);
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let handler = std::sync::Arc::clone(&handler);
+            std::thread::spawn(move || handle(stream, &*handler));
+        }
+    });
+    endpoint
+}
+
+/// Starts a fake service like `serve` and returns a client sending its calls to it.
+pub(crate) fn client<F>(handler: F) -> Random
+where
+    F: Fn(&Value) -> Value + Send + Sync + 'static,
+{
+    Random::new("API KEY").with_endpoint(serve(handler))
+}
+
+fn handle(stream: TcpStream, handler: &dyn Fn(&Value) -> Value) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut writer = stream;
+    loop {
+        let mut content_length = 0;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                return;
+            }
+            let header = line.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+            }
+        }
+        let mut body = vec![0; content_length];
+        if reader.read_exact(&mut body).is_err() {
+            return;
+        }
+        let request = serde_json::from_slice(&body).unwrap_or(Value::Null);
+        let response = if is_out_of_range(&request) {
+            error_result(
+                &request,
+                300,
+                "The integers must be within the [-1e9, 1e9] range.",
+            )
+        } else {
+            handler(&request)
+        }
+        .to_string();
+        let written = write!(
+            writer,
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            response.len(),
+            response
+        );
+        if written.is_err() {
+            return;
+        }
+    }
+}
+
+/// Returns `true` if the request asks for integers out of the range the service generates,
+/// which it refuses.
+fn is_out_of_range(request: &Value) -> bool {
+    ["min", "max"].iter().any(|bound| {
+        request["params"][bound]
+            .as_i64()
+            .is_some_and(|bound| !(-1_000_000_000..=1_000_000_000).contains(&bound))
+    })
+}
+
+/// Builds a successful response to `request` carrying the random `data`.
+pub(crate) fn random_result(request: &Value, data: &Value, bits_used: u64) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "result": {
+            "random": {
+                "data": data,
+                "completionTime": "2011-10-10 13:19:12Z"
+            },
+            "bitsUsed": bits_used,
+            "bitsLeft": 1_000_000_000_u64.saturating_sub(bits_used),
+            "requestsLeft": 9_999,
+            "advisoryDelay": 0
+        },
+        "id": request["id"]
+    })
+}
+
+/// Builds a response to `request` carrying the error of the service.
+pub(crate) fn error_result(request: &Value, code: u64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "error": { "code": code, "message": message },
+        "id": request["id"]
+    })
+}
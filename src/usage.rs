@@ -0,0 +1,290 @@
+//! Client-side tracking of the random.org quota and a budget guarding it.
+//!
+//! # Usage
+//!
+//! ```rust,no_run
+//! use randomorg::{Budget, Random};
+//! use std::time::Duration;
+//!
+//! let r = Random::new("API KEY HERE").with_budget(
+//!     Budget::new(Duration::from_secs(3600))
+//!         .max_bits(100_000)
+//!         .max_requests(50)
+//!         .reserve_bits(10_000),
+//! );
+//! println!("Result: {:?}", r.generate_integers(-100, 100, 15, true));
+//! println!("Usage: {:?}", r.usage_snapshot());
+//! ```
+
+use crate::{Error, Result};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// A limit on how much of the random.org quota a `Random` client may spend.
+///
+/// A request which would break the budget is refused with `Error::BudgetExceeded` before it is
+/// sent, using the number of bits the request is expected to cost.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Budget {
+    window: Duration,
+    max_bits: Option<u64>,
+    max_requests: Option<u64>,
+    reserve_bits: Option<u64>,
+}
+
+impl Budget {
+    /// Creates a budget with no limits, which counts bits and requests over the time window.
+    #[must_use]
+    pub fn new(window: Duration) -> Budget {
+        Budget {
+            window,
+            max_bits: None,
+            max_requests: None,
+            reserve_bits: None,
+        }
+    }
+
+    /// Sets the maximum number of bits which may be used within the time window.
+    #[must_use]
+    pub fn max_bits(mut self, max_bits: u64) -> Self {
+        self.max_bits = Some(max_bits);
+        self
+    }
+
+    /// Sets the maximum number of requests which may be made within the time window.
+    #[must_use]
+    pub fn max_requests(mut self, max_requests: u64) -> Self {
+        self.max_requests = Some(max_requests);
+        self
+    }
+
+    /// Sets the number of bits left to the api keys which must never be spent.
+    #[must_use]
+    pub fn reserve_bits(mut self, reserve_bits: u64) -> Self {
+        self.reserve_bits = Some(reserve_bits);
+        self
+    }
+}
+
+/// The quota usage of a `Random` client, as tracked from the responses of the service.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct UsageSnapshot {
+    /// The (estimated) number of remaining true random bits available to the api keys, if known.
+    pub bits_left: Option<u64>,
+    /// The (estimated) number of remaining API requests available to the api keys, if known.
+    pub requests_left: Option<u64>,
+    /// The number of bits used by the client since it was created.
+    pub bits_used: u64,
+    /// The number of requests made by the client since it was created.
+    pub requests_used: u64,
+    /// The number of bits used by the client within the budget time window.
+    pub window_bits_used: u64,
+    /// The number of requests made by the client within the budget time window.
+    pub window_requests_used: u64,
+}
+
+/// Keeps the usage of a client and checks it against the budget.
+#[derive(Debug, Default)]
+pub(crate) struct UsageTracker {
+    budget: Option<Budget>,
+    history: VecDeque<(Instant, u64)>,
+    bits_used: u64,
+    requests_used: u64,
+    reserved_bits: u64,
+    reserved_requests: u64,
+}
+
+impl UsageTracker {
+    pub(crate) fn set_budget(&mut self, budget: Budget) {
+        self.budget = Some(budget);
+    }
+
+    fn prune(&mut self) {
+        let window = match self.budget {
+            Some(ref budget) => budget.window,
+            None => return self.history.clear(),
+        };
+        while self
+            .history
+            .front()
+            .is_some_and(|(t, _)| t.elapsed() >= window)
+        {
+            self.history.pop_front();
+        }
+    }
+
+    /// Checks whether `requests` requests expected to use `estimated_bits` together fit into the
+    /// budget, with `bits_left` to the keys they are sent with. The requests in flight count
+    /// against the budget, so that the clones of a client sharing the tracker can't overspend
+    /// it together.
+    pub(crate) fn check(
+        &mut self,
+        estimated_bits: u64,
+        requests: u64,
+        bits_left: Option<u64>,
+    ) -> Result<()> {
+        self.prune();
+        let Some(ref budget) = self.budget else {
+            return Ok(());
+        };
+        let window_bits =
+            self.history.iter().map(|(_, bits)| bits).sum::<u64>() + self.reserved_bits;
+        let window_requests = self.history.len() as u64 + self.reserved_requests;
+        if budget
+            .max_requests
+            .is_some_and(|max| window_requests.saturating_add(requests) > max)
+        {
+            return Err(Error::BudgetExceeded(
+                "The request would exceed the maximum number of requests.",
+            ));
+        }
+        if budget
+            .max_bits
+            .is_some_and(|max| window_bits.saturating_add(estimated_bits) > max)
+        {
+            return Err(Error::BudgetExceeded(
+                "The request would exceed the maximum number of bits.",
+            ));
+        }
+        if let (Some(reserve), Some(left)) = (budget.reserve_bits, bits_left) {
+            if left.saturating_sub(estimated_bits) < reserve {
+                return Err(Error::BudgetExceeded(
+                    "The request would spend the reserved bits.",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks whether a request expected to use `estimated_bits` fits into the budget, with
+    /// `bits_left` to the key it is sent with, and reserves its bits and request until it is
+    /// recorded or released.
+    pub(crate) fn reserve(&mut self, estimated_bits: u64, bits_left: Option<u64>) -> Result<()> {
+        self.check(estimated_bits, 1, bits_left)?;
+        if self.budget.is_some() {
+            self.reserved_bits += estimated_bits;
+            self.reserved_requests += 1;
+        }
+        Ok(())
+    }
+
+    /// Releases the reservation of a request expected to use `estimated_bits` which was not sent.
+    pub(crate) fn release(&mut self, estimated_bits: u64) {
+        if self.budget.is_some() {
+            self.reserved_bits = self.reserved_bits.saturating_sub(estimated_bits);
+            self.reserved_requests = self.reserved_requests.saturating_sub(1);
+        }
+    }
+
+    /// Records a request reserved with `estimated_bits` which actually used `bits_used` bits.
+    pub(crate) fn record(&mut self, estimated_bits: u64, bits_used: u64) {
+        self.release(estimated_bits);
+        self.bits_used += bits_used;
+        self.requests_used += 1;
+        if self.budget.is_some() {
+            self.history.push_back((Instant::now(), bits_used));
+        }
+    }
+
+    pub(crate) fn snapshot(
+        &mut self,
+        bits_left: Option<u64>,
+        requests_left: Option<u64>,
+    ) -> UsageSnapshot {
+        self.prune();
+        UsageSnapshot {
+            bits_left,
+            requests_left,
+            bits_used: self.bits_used,
+            requests_used: self.requests_used,
+            window_bits_used: self.history.iter().map(|(_, bits)| bits).sum(),
+            window_requests_used: self.history.len() as u64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Budget, UsageTracker};
+    use crate::Error;
+    use std::time::Duration;
+
+    #[test]
+    fn test_budget_refuses_requests_beyond_limits() {
+        let mut tracker = UsageTracker::default();
+        tracker.set_budget(
+            Budget::new(Duration::from_secs(100))
+                .max_bits(100)
+                .max_requests(2),
+        );
+
+        assert!(tracker.reserve(60, None).is_ok());
+        tracker.record(60, 60);
+        assert!(matches!(
+            tracker.reserve(41, None),
+            Err(Error::BudgetExceeded(_))
+        ));
+        assert!(tracker.reserve(40, None).is_ok());
+        tracker.record(40, 40);
+        assert!(matches!(
+            tracker.reserve(0, None),
+            Err(Error::BudgetExceeded(_))
+        ));
+
+        let snapshot = tracker.snapshot(Some(900), Some(8));
+        assert_eq!(snapshot.bits_used, 100);
+        assert_eq!(snapshot.requests_used, 2);
+        assert_eq!(snapshot.window_bits_used, 100);
+        assert_eq!(snapshot.window_requests_used, 2);
+    }
+
+    #[test]
+    fn test_budget_keeps_reserve() {
+        let mut tracker = UsageTracker::default();
+        tracker.set_budget(Budget::new(Duration::from_secs(1)).reserve_bits(1000));
+
+        assert!(tracker.reserve(500, None).is_ok());
+        assert!(tracker.reserve(500, Some(1500)).is_ok());
+        assert!(matches!(
+            tracker.reserve(501, Some(1500)),
+            Err(Error::BudgetExceeded(_))
+        ));
+    }
+
+    #[test]
+    fn test_requests_in_flight_count_against_budget() {
+        let mut tracker = UsageTracker::default();
+        tracker.set_budget(
+            Budget::new(Duration::from_secs(100))
+                .max_bits(100)
+                .max_requests(2),
+        );
+
+        assert!(tracker.reserve(60, None).is_ok());
+        assert!(matches!(
+            tracker.reserve(60, None),
+            Err(Error::BudgetExceeded(_))
+        ));
+        tracker.release(60);
+        assert!(tracker.reserve(60, None).is_ok());
+        tracker.record(60, 20);
+        assert!(tracker.reserve(80, None).is_ok());
+        assert!(matches!(
+            tracker.reserve(0, None),
+            Err(Error::BudgetExceeded(_))
+        ));
+        assert_eq!(tracker.snapshot(None, None).window_bits_used, 20);
+    }
+
+    #[test]
+    fn test_window_expires() {
+        let mut tracker = UsageTracker::default();
+        tracker.set_budget(Budget::new(Duration::from_millis(10)).max_requests(1));
+
+        tracker.record(0, 10);
+        assert!(tracker.reserve(10, None).is_err());
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(tracker.reserve(10, None).is_ok());
+        assert_eq!(tracker.snapshot(None, None).bits_used, 10);
+    }
+}
@@ -0,0 +1,408 @@
+//! Weighted random choice: the items are picked by the values drawn on the random.org side,
+//! mapped to the items through the cumulative weights.
+
+use crate::{Error, Random, RandomResult, Response, Result};
+use std::convert::TryFrom;
+
+/// The number of decimal places of the fractions floating point weights are drawn with.
+const DECIMAL_PLACES: u8 = 14;
+
+/// The weights of a weighted choice.
+///
+/// Integer weights are picked with integers drawn over the `[0, total)` range, so every value
+/// maps to exactly one item and no bias is introduced. Floating point weights are picked with
+/// decimal fractions of 14 decimal places scaled to their total: the fractions being
+/// quantized to steps of 1e-14, the probability of an item is off its share of the total by up
+/// to 1e-14, and an item whose share is below 1e-14 may never be picked. Use integer weights
+/// when an exact distribution matters.
+pub trait Weight: Copy {
+    /// The type of the values drawn to pick the items.
+    type Draw: Copy;
+
+    /// The weight of an item which may not be picked.
+    const ZERO: Self;
+
+    /// Requests `count` values from the service, each one picking an item of `weights`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Rest` if the weights are invalid, and the error of the service if the call
+    /// fails.
+    fn draw(
+        client: &Random,
+        weights: &[Self],
+        count: usize,
+    ) -> Result<Response<RandomResult<Self::Draw>>>;
+
+    /// Returns the index of the item of `weights` picked by a drawn value.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Rest` if the value is out of the range it was drawn over.
+    fn pick(weights: &[Self], draw: Self::Draw) -> Result<usize>;
+
+    /// Returns `true` if the item of the weight may be picked.
+    fn is_positive(self) -> bool;
+}
+
+/// The total of integer weights, which must be drawn in a single range of the service.
+fn integer_total(weights: &[u64]) -> Result<i32> {
+    let total = weights
+        .iter()
+        .try_fold(0u64, |total, &w| total.checked_add(w))
+        .filter(|&total| total <= 1_000_000_001)
+        .ok_or(Error::Rest(
+            "The total of the integer weights must not exceed 1,000,000,001.",
+        ))?;
+    if total == 0 {
+        return Err(Error::Rest("At least one of the weights must be positive."));
+    }
+    i32::try_from(total - 1)
+        .map_err(|_| Error::Rest("The total of the integer weights is too large."))
+}
+
+/// The total of floating point weights, which must be finite and non-negative.
+fn float_total(weights: &[f64]) -> Result<f64> {
+    if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+        return Err(Error::Rest(
+            "The weights must be finite and non-negative numbers.",
+        ));
+    }
+    let total = weights.iter().sum::<f64>();
+    if !total.is_finite() || total <= 0.0 {
+        return Err(Error::Rest(
+            "At least one of the weights must be positive and their total finite.",
+        ));
+    }
+    Ok(total)
+}
+
+macro_rules! integer_weight {
+    ($($weight:ty),*) => {
+        $(impl Weight for $weight {
+            type Draw = i32;
+
+            const ZERO: $weight = 0;
+
+            fn draw(
+                client: &Random,
+                weights: &[$weight],
+                count: usize,
+            ) -> Result<Response<RandomResult<i32>>> {
+                let weights = weights.iter().map(|&w| u64::from(w)).collect::<Vec<u64>>();
+                client
+                    .request_integers()
+                    .min(0)
+                    .max(integer_total(&weights)?)
+                    .count(count as u64)
+                    .replacement(true)
+                    .collect()
+            }
+
+            fn pick(weights: &[$weight], draw: i32) -> Result<usize> {
+                const OUT_OF_RANGE: Error =
+                    Error::Rest("The service returned an integer out of the range.");
+
+                let draw = u64::try_from(draw).map_err(|_| OUT_OF_RANGE)?;
+                let mut cumulative = 0u64;
+                weights
+                    .iter()
+                    .position(|&w| {
+                        cumulative += u64::from(w);
+                        draw < cumulative
+                    })
+                    .ok_or(OUT_OF_RANGE)
+            }
+
+            fn is_positive(self) -> bool {
+                self > 0
+            }
+        })*
+    };
+}
+
+integer_weight!(u8, u16, u32, u64);
+
+macro_rules! float_weight {
+    ($($weight:ty),*) => {
+        $(impl Weight for $weight {
+            type Draw = f64;
+
+            const ZERO: $weight = 0.0;
+
+            fn draw(
+                client: &Random,
+                weights: &[$weight],
+                count: usize,
+            ) -> Result<Response<RandomResult<f64>>> {
+                float_total(&weights.iter().map(|&w| f64::from(w)).collect::<Vec<f64>>())?;
+                client
+                    .request_decimal_fractions()
+                    .count(count as u64)
+                    .decimal_places(DECIMAL_PLACES)
+                    .collect()
+            }
+
+            fn pick(weights: &[$weight], draw: f64) -> Result<usize> {
+                if !(0.0..=1.0).contains(&draw) {
+                    return Err(Error::Rest(
+                        "The service returned a decimal fraction out of the range.",
+                    ));
+                }
+                let weights = weights.iter().map(|&w| f64::from(w)).collect::<Vec<f64>>();
+                let target = draw * weights.iter().sum::<f64>();
+                let mut cumulative = 0.0;
+                // A fraction of 1 picks the last item which may be picked.
+                let last = weights.iter().rposition(|&w| w > 0.0).unwrap_or(0);
+                weights
+                    .iter()
+                    .position(|&w| {
+                        cumulative += w;
+                        w > 0.0 && target < cumulative
+                    })
+                    .map_or(Ok(last), |i| Ok(i.min(last)))
+            }
+
+            fn is_positive(self) -> bool {
+                self > 0.0
+            }
+        })*
+    };
+}
+
+float_weight!(f32, f64);
+
+/// The items picked by a weighted choice.
+#[derive(Debug, Clone)]
+pub struct WeightedPicks<'a, T, D> {
+    /// The picked items, in the order they were drawn.
+    pub picks: Vec<&'a T>,
+    /// The indices of the picked items.
+    pub indices: Vec<usize>,
+    /// The values drawn by the service, merged across the calls, for auditing. Without
+    /// replacement every value is drawn over the total of the items not picked yet. It is
+    /// `None` when no item was picked and no call was made.
+    pub draws: Option<Response<RandomResult<D>>>,
+}
+
+/// A lazy weighted choice (builder)
+pub struct ChooseWeighted<'a, T, W> {
+    client: &'a Random,
+    items: &'a [T],
+    weights: &'a [W],
+    count: usize,
+    replacement: bool,
+}
+
+impl<'a, T, W: Weight> ChooseWeighted<'a, T, W> {
+    /// Creates a lazy weighted choice (builder) picking a single item.
+    pub fn new(client: &'a Random, items: &'a [T], weights: &'a [W]) -> ChooseWeighted<'a, T, W> {
+        ChooseWeighted {
+            client,
+            items,
+            weights,
+            count: 1,
+            replacement: true,
+        }
+    }
+
+    /// A builder method. Sets how many items to pick.
+    #[must_use]
+    pub fn count(mut self, count: usize) -> Self {
+        self.count = count;
+        self
+    }
+
+    /// A builder method. Without replacement an item is picked at most once, the next picks
+    /// being proportional to the weights of the items left.
+    #[must_use]
+    pub fn replacement(mut self, replacement: bool) -> Self {
+        self.replacement = replacement;
+        self
+    }
+
+    /// Picks the items (performs the requests).
+    ///
+    /// With replacement the values are drawn in a single call. Without replacement a value is
+    /// drawn per item, over the total of the weights of the items not picked yet, so that the
+    /// picks are proportional to the weights of the items left; the advisory delay of the
+    /// service is respected between the calls.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Rest` if every item does not have a single weight, or if more different
+    /// items are to be picked than have a positive weight, and the error of the service if a
+    /// call fails.
+    pub fn collect(self) -> Result<WeightedPicks<'a, T, W::Draw>> {
+        if self.items.len() != self.weights.len() {
+            return Err(Error::Rest("Every item must have a single weight."));
+        }
+        let positive = self.weights.iter().filter(|w| w.is_positive()).count();
+        if !self.replacement && self.count > positive {
+            return Err(Error::Rest(
+                "Can't pick more different items than there are items with a positive weight.",
+            ));
+        }
+        let mut weights = self.weights.to_vec();
+        let mut indices = Vec::with_capacity(self.count);
+        let mut draws: Option<Response<RandomResult<W::Draw>>> = None;
+        while indices.len() < self.count {
+            if let Some(ref draws) = draws {
+                std::thread::sleep(std::time::Duration::from_millis(
+                    draws.result.advisory_delay,
+                ));
+            }
+            let left = self.count - indices.len();
+            let count = if self.replacement { left } else { 1 };
+            let response = W::draw(self.client, &weights, count)?;
+            for &draw in response.result.random.data.iter().take(left) {
+                let index = W::pick(&weights, draw)?;
+                indices.push(index);
+                if !self.replacement {
+                    weights[index] = W::ZERO;
+                }
+            }
+            match draws {
+                Some(ref mut draws) => draws.append(response),
+                None => draws = Some(response),
+            }
+        }
+        Ok(WeightedPicks {
+            picks: indices.iter().map(|&i| &self.items[i]).collect(),
+            indices,
+            draws,
+        })
+    }
+}
+
+impl Random {
+    /// Create a weighted choice of the `items`, each one picked with a probability
+    /// proportional to its weight.
+    ///
+    /// # Usage
+    ///
+    /// ```rust,no_run
+    /// extern crate randomorg;
+    ///
+    /// fn main() {
+    ///     use randomorg::Random;
+    ///     let r = Random::new("API KEY HERE");
+    ///     let prizes = ["car", "phone", "mug"];
+    ///     let choice = r.choose_weighted(&prizes, &[1u32, 10, 100]).count(3).collect().unwrap();
+    ///     println!("Prizes: {:?}, draws: {:?}", choice.picks, choice.draws);
+    /// }
+    /// ```
+    ///
+    /// # Constraints
+    /// * every item must have a weight
+    /// * the total of integer weights must be within [1; 1e9 + 1] range
+    /// * floating point weights must be finite and non-negative, with a positive total
+    pub fn choose_weighted<'a, T, W: Weight>(
+        &'a self,
+        items: &'a [T],
+        weights: &'a [W],
+    ) -> ChooseWeighted<'a, T, W> {
+        ChooseWeighted::new(self, items, weights)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_server::{client, random_result};
+    use crate::Random;
+    use std::convert::TryFrom;
+
+    /// Serves the integers 0, 2, 3, 10, 9 in a loop, carrying on from one call to the next,
+    /// wrapped into the requested range.
+    fn weighted_server() -> Random {
+        let served = std::sync::atomic::AtomicUsize::new(0);
+        client(move |request| {
+            let params = &request["params"];
+            let n = usize::try_from(params["n"].as_u64().unwrap()).unwrap();
+            let data = match request["method"].as_str().unwrap() {
+                "generateIntegers" => {
+                    assert_eq!(params["min"], 0);
+                    let max = params["max"].as_i64().unwrap();
+                    let first = served.fetch_add(n, std::sync::atomic::Ordering::SeqCst);
+                    serde_json::json!((first..first + n)
+                        .map(|i| [0, 2, 3, 10, 9][i % 5] % (max + 1))
+                        .collect::<Vec<i64>>())
+                }
+                "generateDecimalFractions" => serde_json::json!([0.1, 0.5, 1.0]),
+                method => panic!("Unexpected method {}", method),
+            };
+            random_result(request, &data, 4)
+        })
+    }
+
+    #[test]
+    fn test_integer_weights_pick_by_range() {
+        let items = ['a', 'b', 'c', 'd'];
+        let weights = [3u8, 0, 7, 1];
+        let random = weighted_server();
+        let choice = random
+            .choose_weighted(&items, &weights)
+            .count(5)
+            .collect()
+            .unwrap();
+        assert_eq!(choice.picks, vec![&'a', &'a', &'c', &'d', &'c']);
+        assert_eq!(
+            choice.draws.unwrap().result.random.data,
+            vec![0, 2, 3, 10, 9]
+        );
+
+        // After `a` is picked, the next values are drawn over the [0, 8) and [0, 1) ranges.
+        let random = weighted_server();
+        let choice = random
+            .choose_weighted(&items, &weights)
+            .count(3)
+            .replacement(false)
+            .collect()
+            .unwrap();
+        assert_eq!(choice.indices, vec![0, 2, 3]);
+        assert_eq!(choice.draws.unwrap().result.random.data, vec![0, 2, 0]);
+        let choice = random
+            .choose_weighted(&items[..2], &[1_000_000u32, 1])
+            .count(2)
+            .replacement(false)
+            .collect()
+            .unwrap();
+        assert_eq!(choice.indices, vec![0, 1]);
+        assert_eq!(choice.draws.unwrap().result.random.data.len(), 2);
+        assert!(random
+            .choose_weighted(&items, &weights)
+            .count(4)
+            .replacement(false)
+            .collect()
+            .is_err());
+        assert!(random.choose_weighted(&items, &[1u8]).collect().is_err());
+
+        // A value out of the [0, 11) range is refused instead of picking an item.
+        for value in [-1, 11] {
+            let random =
+                client(move |request| random_result(request, &serde_json::json!([value]), 4));
+            assert!(random.choose_weighted(&items, &weights).collect().is_err());
+        }
+        let random = client(|request| random_result(request, &serde_json::json!([1.5]), 4));
+        assert!(random
+            .choose_weighted(&items[..1], &[1.0])
+            .collect()
+            .is_err());
+    }
+
+    #[test]
+    fn test_float_weights_pick_by_fraction() {
+        let random = weighted_server();
+        let items = ['a', 'b', 'c'];
+        let choice = random
+            .choose_weighted(&items, &[0.25, 0.5, 0.0])
+            .count(3)
+            .collect()
+            .unwrap();
+        assert_eq!(choice.indices, vec![0, 1, 1]);
+        assert!(random
+            .choose_weighted(&items, &[1.0, f64::NAN, 1.0])
+            .collect()
+            .is_err());
+    }
+}